@@ -1,12 +1,21 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path, PathBuf};
-use tauri::Manager;
-
-const ACCOUNT_FILE: &str = "account_settings.json";
-const PREFERENCES_FILE: &str = "user_preferences.json";
-const SESSION_FILE: &str = "auth_session.json";
-const PROFILE_IMAGE_DIR: &str = "profile_images";
+use std::path::PathBuf;
+use tauri::Emitter;
+
+use crate::storage::{self, config_path};
+use crate::{alarm, api, entitlement, workspace};
+
+pub const ACCOUNT_KEY: &str = "account_settings";
+pub const PREFERENCES_KEY: &str = "user_preferences";
+const SESSION_KEY: &str = "auth_session";
+pub const PROFILE_IMAGE_DIR: &str = "profile_images";
+const REVOKE_ENDPOINT: &str = "/api/auth/external/logout";
+/// 클라이언트/서버 시계 차이 허용 범위
+const CLOCK_SKEW_TOLERANCE_SECS: i64 = 30;
+/// 만료 전 이 시간 이내면 expiring_soon 으로 판단
+const EXPIRING_SOON_WINDOW_SECS: i64 = 5 * 60;
+const SESSION_WATCHDOG_INTERVAL_SECS: u64 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountSettings {
@@ -48,6 +57,43 @@ pub struct AuthSession {
     pub expires_at_unix: Option<i64>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Valid,
+    ExpiringSoon,
+    Expired,
+    Refreshable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthSessionInfo {
+    pub status: SessionStatus,
+    /// 만료까지 남은 초 (만료 시각을 모르면 None)
+    pub expires_in_secs: Option<i64>,
+    pub session: AuthSession,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExpiryPayload {
+    pub status: SessionStatus,
+    pub expires_at_unix: Option<i64>,
+    pub expires_in_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogoutResult {
+    /// 서버에서 refresh token 폐기에 성공했는지 여부
+    pub revoked: bool,
+    /// 프로필 이미지 / 계정 설정 캐시까지 삭제했는지 여부
+    pub purged_cache: bool,
+}
+
+#[derive(Serialize)]
+struct RevokeRequest<'a> {
+    refresh_token: &'a str,
+}
+
 fn default_nickname() -> String {
     "DeskCal User".to_string()
 }
@@ -97,27 +143,40 @@ fn default_auth_session() -> AuthSession {
     }
 }
 
-fn config_path(app: &tauri::AppHandle, filename: &str) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(filename))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+pub fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
 }
 
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
+fn has_token(token: &Option<String>) -> bool {
+    token.as_deref().map(|t| !t.is_empty()).unwrap_or(false)
 }
 
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+fn evaluate_session(session: &AuthSession, now: i64) -> SessionStatus {
+    let expired_status = if has_token(&session.refresh_token) {
+        SessionStatus::Refreshable
+    } else {
+        SessionStatus::Expired
+    };
+
+    if !has_token(&session.access_token) {
+        return expired_status;
     }
 
-    let json = serde_json::to_string_pretty(value)
-        .map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
+    let expires_at = match session.expires_at_unix {
+        Some(expires_at) => expires_at,
+        None => return SessionStatus::Valid,
+    };
+
+    if now + CLOCK_SKEW_TOLERANCE_SECS >= expires_at {
+        expired_status
+    } else if expires_at - now <= EXPIRING_SOON_WINDOW_SECS + CLOCK_SKEW_TOLERANCE_SECS {
+        SessionStatus::ExpiringSoon
+    } else {
+        SessionStatus::Valid
+    }
 }
 
 fn normalize_nickname(nickname: &str) -> String {
@@ -133,51 +192,56 @@ fn validate_nickname(nickname: &str) -> Result<String, String> {
     Ok(trimmed.to_string())
 }
 
-fn load_account_settings(app: &tauri::AppHandle) -> AccountSettings {
-    let path = match config_path(app, ACCOUNT_FILE) {
-        Ok(path) => path,
-        Err(_) => return default_account_settings(),
-    };
-    read_json::<AccountSettings>(&path).unwrap_or_else(default_account_settings)
+pub fn load_account_settings(app: &tauri::AppHandle) -> AccountSettings {
+    storage::read_json_or_log::<AccountSettings>(app, ACCOUNT_KEY).unwrap_or_else(default_account_settings)
 }
 
-fn save_account_settings(app: &tauri::AppHandle, settings: &AccountSettings) -> Result<(), String> {
-    let path = config_path(app, ACCOUNT_FILE)?;
-    write_json(&path, settings)
+/// 계정 설정 읽기-수정-쓰기를 한 트랜잭션 안에서 수행
+pub fn update_account_settings<R>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&mut AccountSettings) -> Result<R, String>,
+) -> Result<(AccountSettings, R), String> {
+    storage::update_json(
+        app,
+        ACCOUNT_KEY,
+        |loaded| loaded.unwrap_or_else(default_account_settings),
+        f,
+    )
 }
 
-fn load_preferences(app: &tauri::AppHandle) -> UserPreferences {
-    let path = match config_path(app, PREFERENCES_FILE) {
-        Ok(path) => path,
-        Err(_) => return default_user_preferences(),
-    };
-    read_json::<UserPreferences>(&path).unwrap_or_else(default_user_preferences)
+pub fn load_preferences(app: &tauri::AppHandle) -> UserPreferences {
+    storage::read_json_or_log::<UserPreferences>(app, PREFERENCES_KEY)
+        .unwrap_or_else(default_user_preferences)
 }
 
 fn save_preferences(app: &tauri::AppHandle, preferences: &UserPreferences) -> Result<(), String> {
-    let path = config_path(app, PREFERENCES_FILE)?;
-    write_json(&path, preferences)
+    storage::write_json(app, PREFERENCES_KEY, preferences)
 }
 
-fn load_session(app: &tauri::AppHandle) -> AuthSession {
-    let path = match config_path(app, SESSION_FILE) {
-        Ok(path) => path,
-        Err(_) => return default_auth_session(),
-    };
-    read_json::<AuthSession>(&path).unwrap_or_else(default_auth_session)
+pub fn load_session(app: &tauri::AppHandle) -> AuthSession {
+    storage::read_json_or_log::<AuthSession>(app, SESSION_KEY).unwrap_or_else(default_auth_session)
 }
 
-fn save_session(app: &tauri::AppHandle, session: &AuthSession) -> Result<(), String> {
-    let path = config_path(app, SESSION_FILE)?;
-    write_json(&path, session)
+pub fn save_session(app: &tauri::AppHandle, session: &AuthSession) -> Result<(), String> {
+    storage::write_json(app, SESSION_KEY, session)
 }
 
 fn clear_session(app: &tauri::AppHandle) -> Result<(), String> {
-    let path = config_path(app, SESSION_FILE)?;
-    if path.exists() {
-        fs::remove_file(path).map_err(|e| format!("Failed to remove session file: {}", e))?;
+    storage::remove(app, SESSION_KEY)
+}
+
+/// Rust 쪽에서 서버 API 를 호출할 때 사용할 유효한 access token
+pub fn access_token(app: &tauri::AppHandle) -> Result<String, String> {
+    let session = load_session(app);
+    match evaluate_session(&session, now_unix()) {
+        SessionStatus::Valid | SessionStatus::ExpiringSoon => session
+            .access_token
+            .ok_or_else(|| "Not signed in".to_string()),
+        SessionStatus::Refreshable | SessionStatus::Expired if has_token(&session.access_token) => {
+            Err("Session expired".to_string())
+        }
+        _ => Err("Not signed in".to_string()),
     }
-    Ok(())
 }
 
 #[tauri::command]
@@ -190,9 +254,11 @@ pub fn set_reserved_nicknames(
     app: tauri::AppHandle,
     reserved_nicknames: Vec<String>,
 ) -> Result<(), String> {
-    let mut settings = load_account_settings(&app);
-    settings.reserved_nicknames = reserved_nicknames;
-    save_account_settings(&app, &settings)
+    update_account_settings(&app, |settings| {
+        settings.reserved_nicknames = reserved_nicknames;
+        Ok(())
+    })?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -215,20 +281,21 @@ pub fn is_nickname_available(app: tauri::AppHandle, nickname: String) -> Result<
 #[tauri::command]
 pub fn update_nickname(app: tauri::AppHandle, nickname: String) -> Result<AccountSettings, String> {
     let nickname = validate_nickname(&nickname)?;
-    let mut settings = load_account_settings(&app);
 
-    let duplicated = settings
-        .reserved_nicknames
-        .iter()
-        .any(|used| normalize_nickname(used) == normalize_nickname(&nickname))
-        && normalize_nickname(&settings.nickname) != normalize_nickname(&nickname);
+    let (settings, _) = update_account_settings(&app, |settings| {
+        let duplicated = settings
+            .reserved_nicknames
+            .iter()
+            .any(|used| normalize_nickname(used) == normalize_nickname(&nickname))
+            && normalize_nickname(&settings.nickname) != normalize_nickname(&nickname);
 
-    if duplicated {
-        return Err("Nickname is already in use".to_string());
-    }
+        if duplicated {
+            return Err("Nickname is already in use".to_string());
+        }
 
-    settings.nickname = nickname;
-    save_account_settings(&app, &settings)?;
+        settings.nickname = nickname;
+        Ok(())
+    })?;
     Ok(settings)
 }
 
@@ -263,33 +330,33 @@ pub fn upload_profile_image(app: tauri::AppHandle, source_path: String) -> Resul
     let target = profile_dir.join(filename);
     fs::copy(&source, &target).map_err(|e| format!("Failed to copy profile image: {}", e))?;
 
-    let mut settings = load_account_settings(&app);
-    if let Some(old_path) = settings.profile_image_path.as_deref() {
+    let target_str = target.to_string_lossy().to_string();
+    let (_, old_path) = update_account_settings(&app, |settings| {
+        Ok(settings.profile_image_path.replace(target_str.clone()))
+    })?;
+
+    if let Some(old_path) = old_path {
         let old = PathBuf::from(old_path);
         if old.exists() {
             let _ = fs::remove_file(old);
         }
     }
 
-    let target_str = target.to_string_lossy().to_string();
-    settings.profile_image_path = Some(target_str.clone());
-    save_account_settings(&app, &settings)?;
-
     Ok(target_str)
 }
 
 #[tauri::command]
 pub fn delete_profile_image(app: tauri::AppHandle) -> Result<(), String> {
-    let mut settings = load_account_settings(&app);
-    if let Some(path) = settings.profile_image_path.as_deref() {
+    let (_, old_path) =
+        update_account_settings(&app, |settings| Ok(settings.profile_image_path.take()))?;
+    if let Some(path) = old_path {
         let profile = PathBuf::from(path);
         if profile.exists() {
             fs::remove_file(profile)
                 .map_err(|e| format!("Failed to delete profile image: {}", e))?;
         }
     }
-    settings.profile_image_path = None;
-    save_account_settings(&app, &settings)
+    Ok(())
 }
 
 #[tauri::command]
@@ -306,9 +373,22 @@ pub fn save_user_preferences(
     Ok(preferences)
 }
 
+/// 세션과 함께 만료 상태를 반환. 만료된 세션의 access token 은 내려주지 않는다.
 #[tauri::command]
-pub fn get_auth_session(app: tauri::AppHandle) -> AuthSession {
-    load_session(&app)
+pub fn get_auth_session(app: tauri::AppHandle) -> AuthSessionInfo {
+    let mut session = load_session(&app);
+    let now = now_unix();
+    let status = evaluate_session(&session, now);
+
+    if matches!(status, SessionStatus::Expired | SessionStatus::Refreshable) {
+        session.access_token = None;
+    }
+
+    AuthSessionInfo {
+        status,
+        expires_in_secs: session.expires_at_unix.map(|expires_at| expires_at - now),
+        session,
+    }
 }
 
 #[tauri::command]
@@ -321,7 +401,116 @@ pub fn clear_auth_session(app: tauri::AppHandle) -> Result<(), String> {
     clear_session(&app)
 }
 
+fn purge_profile_images(app: &tauri::AppHandle) -> Result<(), String> {
+    let profile_dir = config_path(app, PROFILE_IMAGE_DIR)?;
+    if profile_dir.exists() {
+        fs::remove_dir_all(profile_dir)
+            .map_err(|e| format!("Failed to remove profile image directory: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn revoke_session(session: &AuthSession) -> bool {
+    let refresh_token = match session.refresh_token.as_deref() {
+        Some(token) if !token.is_empty() => token,
+        _ => return false,
+    };
+
+    let request = RevokeRequest { refresh_token };
+    match api::post_json::<_, serde_json::Value>(
+        REVOKE_ENDPOINT,
+        session.access_token.as_deref(),
+        &request,
+    )
+    .await
+    {
+        Ok(_) => true,
+        Err(e) => {
+            log::warn!("logout: token revocation failed: {}", e);
+            false
+        }
+    }
+}
+
+/// 서버 토큰 폐기 후 사용자별 로컬 상태를 지우고 모든 창에 `auth://logged-out` 전송.
+/// 토큰 폐기가 실패해도 로컬 정리는 계속 진행한다.
 #[tauri::command]
-pub fn logout(app: tauri::AppHandle) -> Result<(), String> {
-    clear_session(&app)
+pub async fn logout(
+    app: tauri::AppHandle,
+    purge_cache: Option<bool>,
+) -> Result<LogoutResult, String> {
+    let session = load_session(&app);
+    let revoked = revoke_session(&session).await;
+
+    let purged_cache = purge_cache.unwrap_or(false);
+    storage::transaction(&app, |tx| {
+        tx.delete(SESSION_KEY)?;
+        tx.delete(alarm::ALARM_STATE_KEY)?;
+        tx.delete(workspace::WORKSPACE_STATE_KEY)?;
+        tx.delete(entitlement::ENTITLEMENTS_KEY)?;
+        if purged_cache {
+            tx.delete(ACCOUNT_KEY)?;
+        }
+        Ok(())
+    })?;
+    alarm::reload_alarm_manager(&app)?;
+    workspace::notify_reloaded(&app)?;
+
+    if purged_cache {
+        purge_profile_images(&app)?;
+    }
+
+    let result = LogoutResult {
+        revoked,
+        purged_cache,
+    };
+    if let Err(e) = app.emit("auth://logged-out", result.clone()) {
+        log::warn!("logout: failed to emit logged-out event: {}", e);
+    }
+    Ok(result)
+}
+
+/// 세션 만료 감시. 만료 임박 시 `auth://session-expiring`, 만료 시 `auth://session-expired` 를
+/// 같은 만료 시각에 대해 한 번씩만 전송한다.
+pub fn start_session_watchdog(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut notified_expiring: Option<i64> = None;
+        let mut notified_expired: Option<i64> = None;
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(SESSION_WATCHDOG_INTERVAL_SECS));
+
+            let session = load_session(&app);
+            if !has_token(&session.access_token) {
+                continue;
+            }
+            let expires_at = match session.expires_at_unix {
+                Some(expires_at) => expires_at,
+                None => continue,
+            };
+
+            let now = now_unix();
+            let status = evaluate_session(&session, now);
+            let (event, notified) = match status {
+                SessionStatus::Valid => continue,
+                SessionStatus::ExpiringSoon => ("auth://session-expiring", &mut notified_expiring),
+                SessionStatus::Expired | SessionStatus::Refreshable => {
+                    ("auth://session-expired", &mut notified_expired)
+                }
+            };
+            if *notified == Some(expires_at) {
+                continue;
+            }
+            *notified = Some(expires_at);
+
+            let payload = SessionExpiryPayload {
+                status,
+                expires_at_unix: Some(expires_at),
+                expires_in_secs: Some(expires_at - now),
+            };
+            if let Err(e) = app.emit(event, payload) {
+                log::warn!("session: failed to emit {}: {}", event, e);
+            }
+        }
+    });
 }
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager, State};
 
-const ALARM_STATE_FILE: &str = "alarm_state.json";
+use crate::{account, storage};
+use crate::workspace::WorkspaceRef;
+
+pub const ALARM_STATE_KEY: &str = "alarm_state";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -20,7 +21,7 @@ pub enum AlarmStatus {
 pub struct AlarmRecord {
     pub alarm_id: String,
     pub task_id: i64,
-    pub workspace_id: i64,
+    pub workspace_id: WorkspaceRef,
     pub title: String,
     pub start_at_unix: i64,
     pub trigger_at_unix: i64,
@@ -44,7 +45,7 @@ pub struct AlarmManagerState {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskAlarmInput {
     pub task_id: i64,
-    pub workspace_id: i64,
+    pub workspace_id: WorkspaceRef,
     pub title: String,
     pub start_at_unix: i64,
     #[serde(default)]
@@ -57,19 +58,12 @@ pub struct TaskAlarmInput {
 pub struct AlarmTriggeredPayload {
     pub alarm_id: String,
     pub task_id: i64,
-    pub workspace_id: i64,
+    pub workspace_id: WorkspaceRef,
     pub title: String,
     pub message: String,
     pub scheduled_start_at_unix: i64,
 }
 
-fn now_unix() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
-}
-
 fn default_notifications_enabled() -> bool {
     true
 }
@@ -81,42 +75,28 @@ fn default_state() -> AlarmManagerState {
     }
 }
 
-fn alarm_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(ALARM_STATE_FILE))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+/// 메모리 상태 전체를 다시 저장하므로 DB 오류를 기본값으로 덮지 않고 에러로 돌려준다
+pub fn load_alarm_manager(app: &tauri::AppHandle) -> Result<AlarmManagerState, String> {
+    Ok(storage::read_json::<AlarmManagerState>(app, ALARM_STATE_KEY)?.unwrap_or_else(default_state))
 }
 
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
+fn save_alarm_manager(app: &tauri::AppHandle, state: &AlarmManagerState) -> Result<(), String> {
+    storage::write_json(app, ALARM_STATE_KEY, state)
 }
 
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+/// 가져오기·로그아웃 등으로 저장소 상태가 바뀐 뒤 메모리 상태를 다시 읽음
+pub fn reload_alarm_manager(app: &tauri::AppHandle) -> Result<(), String> {
+    let loaded = load_alarm_manager(app)?;
+    if let Some(state) = app.try_state::<Mutex<AlarmManagerState>>() {
+        let mut guard = state
+            .lock()
+            .map_err(|_| "Failed to lock alarm state".to_string())?;
+        *guard = loaded;
     }
-    let json = serde_json::to_string_pretty(value)
-        .map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
-}
-
-pub fn load_alarm_manager(app: &tauri::AppHandle) -> AlarmManagerState {
-    let path = match alarm_state_path(app) {
-        Ok(path) => path,
-        Err(_) => return default_state(),
-    };
-    read_json::<AlarmManagerState>(&path).unwrap_or_else(default_state)
-}
-
-fn save_alarm_manager(app: &tauri::AppHandle, state: &AlarmManagerState) -> Result<(), String> {
-    let path = alarm_state_path(app)?;
-    write_json(&path, state)
+    Ok(())
 }
 
-fn alarm_id_for_task(workspace_id: i64, task_id: i64, start_at_unix: i64) -> String {
+fn alarm_id_for_task(workspace_id: &WorkspaceRef, task_id: i64, start_at_unix: i64) -> String {
     format!("task:{}:{}:{}", workspace_id, task_id, start_at_unix)
 }
 
@@ -124,13 +104,13 @@ fn build_alarm_from_input(input: &TaskAlarmInput, now: i64) -> AlarmRecord {
     let reminder = input.reminder_minutes_before.unwrap_or(10).max(0);
     let trigger_at = input.start_at_unix - (reminder * 60);
     let enabled = input.is_enabled.unwrap_or(true);
-    let alarm_id = alarm_id_for_task(input.workspace_id, input.task_id, input.start_at_unix);
+    let alarm_id = alarm_id_for_task(&input.workspace_id, input.task_id, input.start_at_unix);
 
     if !enabled || input.start_at_unix <= now {
         return AlarmRecord {
             alarm_id,
             task_id: input.task_id,
-            workspace_id: input.workspace_id,
+            workspace_id: input.workspace_id.clone(),
             title: input.title.clone(),
             start_at_unix: input.start_at_unix,
             trigger_at_unix: trigger_at,
@@ -147,7 +127,7 @@ fn build_alarm_from_input(input: &TaskAlarmInput, now: i64) -> AlarmRecord {
     AlarmRecord {
         alarm_id,
         task_id: input.task_id,
-        workspace_id: input.workspace_id,
+        workspace_id: input.workspace_id.clone(),
         title: input.title.clone(),
         start_at_unix: input.start_at_unix,
         trigger_at_unix: trigger_at,
@@ -186,7 +166,7 @@ pub fn get_alarm_manager_state(state: State<'_, Mutex<AlarmManagerState>>) -> Re
 pub fn clear_workspace_task_alarms(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
-    workspace_id: i64,
+    workspace_id: WorkspaceRef,
 ) -> Result<(), String> {
     let mut guard = state
         .lock()
@@ -197,14 +177,57 @@ pub fn clear_workspace_task_alarms(
     save_alarm_manager(&app, &guard)
 }
 
+/// 워크스페이스 삭제 시 호출 — 해당 워크스페이스의 알람을 모두 제거하고 제거된 수를 반환
+pub fn clear_workspace_alarms(app: &tauri::AppHandle, refs: &[WorkspaceRef]) -> Result<usize, String> {
+    let state = match app.try_state::<Mutex<AlarmManagerState>>() {
+        Some(state) => state,
+        None => return Ok(0),
+    };
+    let mut guard = state
+        .lock()
+        .map_err(|_| "Failed to lock alarm state".to_string())?;
+    let before = guard.alarms.len();
+    guard.alarms.retain(|alarm| !refs.contains(&alarm.workspace_id));
+    let removed = before - guard.alarms.len();
+    if removed > 0 {
+        save_alarm_manager(app, &guard)?;
+    }
+    Ok(removed)
+}
+
+/// 워크스페이스 id 가 바뀌었을 때 로컬 id 로 연결된 알람을 새 id 로 옮긴다. 바뀐 것이 있으면 true.
+pub fn rename_workspace_refs(
+    state: &mut AlarmManagerState,
+    renamed: &HashMap<String, String>,
+) -> bool {
+    let mut changed = false;
+    for alarm in state.alarms.iter_mut() {
+        let new_id = match &alarm.workspace_id {
+            WorkspaceRef::Local(id) => renamed.get(id).cloned(),
+            WorkspaceRef::Server(_) => None,
+        };
+        if let Some(new_id) = new_id {
+            alarm.workspace_id = WorkspaceRef::Local(new_id);
+            // task 알람 id 에도 워크스페이스 id 가 들어 있다
+            if alarm.alarm_id.starts_with("task:") {
+                alarm.alarm_id =
+                    alarm_id_for_task(&alarm.workspace_id, alarm.task_id, alarm.start_at_unix);
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
 #[tauri::command]
 pub fn sync_task_alarms(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
     alarms: Vec<TaskAlarmInput>,
 ) -> Result<usize, String> {
-    let now = now_unix();
-    let incoming_workspace_ids: HashSet<i64> = alarms.iter().map(|a| a.workspace_id).collect();
+    let now = account::now_unix();
+    let incoming_workspace_ids: HashSet<WorkspaceRef> =
+        alarms.iter().map(|a| a.workspace_id.clone()).collect();
 
     let mut incoming_ids: HashSet<String> = HashSet::new();
     let mut normalized: Vec<AlarmRecord> = Vec::new();
@@ -270,7 +293,7 @@ pub fn snooze_alarm(
     let mut guard = state
         .lock()
         .map_err(|_| "Failed to lock alarm state".to_string())?;
-    let now = now_unix();
+    let now = account::now_unix();
     let snooze_until = now + (minutes.max(1) * 60);
 
     let alarm = guard
@@ -295,7 +318,7 @@ pub fn dismiss_alarm(
     let mut guard = state
         .lock()
         .map_err(|_| "Failed to lock alarm state".to_string())?;
-    let now = now_unix();
+    let now = account::now_unix();
 
     let alarm = guard
         .alarms
@@ -313,7 +336,7 @@ pub fn dismiss_alarm(
 pub fn start_alarm_scheduler(app: AppHandle) {
     std::thread::spawn(move || loop {
         std::thread::sleep(std::time::Duration::from_secs(15));
-        let now = now_unix();
+        let now = account::now_unix();
 
         let mut triggered: Vec<AlarmTriggeredPayload> = Vec::new();
         let mut should_save = false;
@@ -341,7 +364,7 @@ pub fn start_alarm_scheduler(app: AppHandle) {
                         triggered.push(AlarmTriggeredPayload {
                             alarm_id: alarm.alarm_id.clone(),
                             task_id: alarm.task_id,
-                            workspace_id: alarm.workspace_id,
+                            workspace_id: alarm.workspace_id.clone(),
                             title: alarm.title.clone(),
                             message: format!("{} 일정 시간이 되었습니다.", alarm.title),
                             scheduled_start_at_unix: alarm.start_at_unix,
@@ -0,0 +1,119 @@
+/// Minimal client for the DeskCal web API, used by commands that must talk to the
+/// server from Rust instead of the webview. The `*_external` helpers take absolute URLs
+/// for third-party endpoints such as OAuth providers.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri_plugin_http::reqwest;
+
+const DEFAULT_API_BASE_URL: &str = "https://trabien.com";
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// 빌드 시 `VITE_API_BASE_URL`이 지정되면 프론트엔드와 같은 서버를 사용
+pub fn base_url() -> &'static str {
+    option_env!("VITE_API_BASE_URL")
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or(DEFAULT_API_BASE_URL)
+        .trim_end_matches('/')
+}
+
+pub fn url(path: &str) -> String {
+    format!("{}{}", base_url(), path)
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to build http client: {}", e))
+}
+
+async fn send(request: reqwest::RequestBuilder) -> Result<String, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("Server responded with {}", status.as_u16()));
+    }
+    Ok(body)
+}
+
+/// 본문이 비어 있으면 `null` 로 간주 (`serde_json::Value` / `Option` 응답용)
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, String> {
+    let body = if body.trim().is_empty() { "null" } else { body };
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+fn with_token(request: reqwest::RequestBuilder, access_token: Option<&str>) -> reqwest::RequestBuilder {
+    match access_token {
+        Some(token) if !token.is_empty() => request.bearer_auth(token),
+        _ => request,
+    }
+}
+
+pub async fn get_json<T: DeserializeOwned>(path: &str, access_token: Option<&str>) -> Result<T, String> {
+    let request = with_token(client()?.get(url(path)), access_token);
+    let body = send(request).await?;
+    parse_body(&body)
+}
+
+pub async fn post_json<B: Serialize, T: DeserializeOwned>(
+    path: &str,
+    access_token: Option<&str>,
+    payload: &B,
+) -> Result<T, String> {
+    let json = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize json: {}", e))?;
+    let request = with_token(client()?.post(url(path)), access_token)
+        .header("Content-Type", "application/json")
+        .body(json);
+    let body = send(request).await?;
+    parse_body(&body)
+}
+
+pub async fn patch_json<B: Serialize, T: DeserializeOwned>(
+    path: &str,
+    access_token: Option<&str>,
+    payload: &B,
+) -> Result<T, String> {
+    let json = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize json: {}", e))?;
+    let request = with_token(client()?.patch(url(path)), access_token)
+        .header("Content-Type", "application/json")
+        .body(json);
+    let body = send(request).await?;
+    parse_body(&body)
+}
+
+pub async fn delete(path: &str, access_token: Option<&str>) -> Result<(), String> {
+    let request = with_token(client()?.delete(url(path)), access_token);
+    send(request).await.map(|_| ())
+}
+
+/// 절대 URL 로 GET (OIDC discovery, userinfo 등 외부 엔드포인트)
+pub async fn get_json_external<T: DeserializeOwned>(
+    url: &str,
+    access_token: Option<&str>,
+) -> Result<T, String> {
+    let request = with_token(client()?.get(url), access_token);
+    let body = send(request).await?;
+    parse_body(&body)
+}
+
+/// 절대 URL 로 `application/x-www-form-urlencoded` POST (OAuth 토큰 엔드포인트)
+pub async fn post_form_external<T: DeserializeOwned>(
+    url: &str,
+    form: &[(&str, &str)],
+) -> Result<T, String> {
+    let request = client()?
+        .post(url)
+        .header("Accept", "application/json")
+        .form(form);
+    let body = send(request).await?;
+    parse_body(&body)
+}
@@ -0,0 +1,693 @@
+/// Typed router for `deskcal://` deep links.
+///
+/// Every link is parsed and validated here before anything reaches the webview. Login
+/// callbacks and window focus are handled in Rust, a workspace link switches the current
+/// workspace, and the rest are emitted to the frontend as a tagged `deep-link://route`
+/// payload. Links that fail validation are reported on `deep-link://invalid` instead.
+///
+/// Links that arrive before the webview has registered its listeners wait in
+/// `DeepLinkQueue` until it calls `frontend_ready` or `drain_pending_deep_links`, so a slow
+/// start no longer loses them. Login callbacks skip the queue since a flow is already waiting.
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::oauth;
+use crate::workspace::{self, WorkspaceRef};
+
+pub const SCHEME: &str = "deskcal://";
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_PENDING: usize = 32;
+/// OS 가 같은 링크를 argv 와 open-url 로 두 번 넘기는 경우를 막는 구간
+const DEDUP_WINDOW: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "route", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `deskcal://` / `deskcal://open` — 창만 앞으로 가져온다
+    Open,
+    /// `deskcal://task/<id>`
+    Task { task_id: i64 },
+    /// `deskcal://workspace/<id>` — 서버 id 또는 로컬 `ws_...` id
+    Workspace { workspace_id: WorkspaceRef },
+    /// `deskcal://date/2026-10-17`
+    Date { date: NaiveDate },
+    /// `deskcal://new?title=..&start=..&end=..`
+    NewTask {
+        title: String,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        /// start/end 가 날짜만 주어졌으면 종일 일정
+        all_day: bool,
+    },
+    /// `deskcal://auth/callback?...` — 로그인 흐름이 처리한다
+    AuthCallback,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidDeepLink {
+    /// 토큰 등 민감한 값을 가린 URL
+    pub url: String,
+    pub error: String,
+}
+
+fn decode(value: &str) -> Result<String, String> {
+    urlencoding::decode(&value.replace('+', " "))
+        .map(|decoded| decoded.into_owned())
+        .map_err(|e| format!("Invalid percent-encoding: {}", e))
+}
+
+fn parse_query(query: &str) -> Result<Vec<(String, String)>, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((decode(key)?, decode(value)?))
+        })
+        .collect()
+}
+
+fn parse_task_id(value: &str) -> Result<i64, String> {
+    match value.parse::<i64>() {
+        Ok(id) if id > 0 && value.chars().all(|c| c.is_ascii_digit()) => Ok(id),
+        _ => Err(format!("Invalid task id: {}", value)),
+    }
+}
+
+fn parse_workspace_ref(value: &str) -> Result<WorkspaceRef, String> {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        return match value.parse::<i64>() {
+            Ok(id) if id > 0 => Ok(WorkspaceRef::Server(id)),
+            _ => Err(format!("Invalid workspace id: {}", value)),
+        };
+    }
+    match value.strip_prefix("ws_") {
+        Some(rest) if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Ok(WorkspaceRef::Local(value.to_string()))
+        }
+        _ => Err(format!("Invalid workspace id: {}", value)),
+    }
+}
+
+/// `YYYY-MM-DD` 만 허용 (한 자리 월·일은 거부)
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    if value.len() != 10 {
+        return Err(format!("Invalid date: {}", value));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))
+}
+
+/// 날짜(`YYYY-MM-DD`), 로컬 시각(`YYYY-MM-DDTHH:MM[:SS]`) 또는 RFC 3339. 두 번째 값은 날짜만 주어졌는지 여부.
+fn parse_time(name: &str, value: &str) -> Result<(NaiveDateTime, bool), String> {
+    if let Ok(date) = parse_date(value) {
+        return Ok((date.and_hms_opt(0, 0, 0).unwrap_or_default(), true));
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok((time.with_timezone(&Local).naive_local(), false));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|time| (time, false))
+        .ok_or_else(|| format!("Invalid {}: {}", name, value))
+}
+
+fn parse_new_task(query: &str) -> Result<DeepLink, String> {
+    let mut title = None;
+    let mut start = None;
+    let mut end = None;
+    for (key, value) in parse_query(query)? {
+        match key.as_str() {
+            "title" => title = Some(value),
+            "start" => start = Some(value),
+            "end" => end = Some(value),
+            // 모르는 파라미터는 무시 (이후 버전에서 추가될 수 있음)
+            _ => {}
+        }
+    }
+
+    let title = title.map(|t| t.trim().to_string()).unwrap_or_default();
+    if title.is_empty() {
+        return Err("title is required".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(format!(
+            "title must be at most {} characters",
+            MAX_TITLE_LENGTH
+        ));
+    }
+
+    let start = start.map(|s| parse_time("start", s.trim())).transpose()?;
+    let end = end.map(|e| parse_time("end", e.trim())).transpose()?;
+    let all_day = match (start, end) {
+        (None, Some(_)) => return Err("end requires start".to_string()),
+        (Some((start, start_date)), Some((end, end_date))) => {
+            if start_date != end_date {
+                return Err("start and end must both be dates or both be times".to_string());
+            }
+            // 종일 일정은 같은 날 끝나도 되지만, 시간 일정은 시작보다 뒤여야 한다
+            if end < start || (!start_date && end == start) {
+                return Err("end must be after start".to_string());
+            }
+            start_date
+        }
+        (Some((_, start_date)), None) => start_date,
+        (None, None) => false,
+    };
+
+    Ok(DeepLink::NewTask {
+        title,
+        start: start.map(|(time, _)| time),
+        end: end.map(|(time, _)| time),
+        all_day,
+    })
+}
+
+/// `deskcal://` URL 을 라우트로 파싱하고 파라미터를 검증
+pub fn parse(url: &str) -> Result<DeepLink, String> {
+    let rest = url
+        .trim()
+        .strip_prefix(SCHEME)
+        .ok_or_else(|| "Not a deskcal:// link".to_string())?;
+    let rest = rest.split('#').next().unwrap_or("");
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode)
+        .collect::<Result<Vec<String>, String>>()?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match segments.as_slice() {
+        [] | ["open"] => Ok(DeepLink::Open),
+        ["task", id] => Ok(DeepLink::Task {
+            task_id: parse_task_id(id)?,
+        }),
+        ["workspace", id] => Ok(DeepLink::Workspace {
+            workspace_id: parse_workspace_ref(id)?,
+        }),
+        ["date", date] => Ok(DeepLink::Date {
+            date: parse_date(date)?,
+        }),
+        ["new"] => parse_new_task(query),
+        ["auth", "callback"] => Ok(DeepLink::AuthCallback),
+        [route, ..] => Err(format!("Unknown deep link route: {}", route)),
+    }
+}
+
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 링크의 워크스페이스(서버 id 또는 로컬 id)를 활성 워크스페이스의 로컬 id 로 바꾼다
+fn resolve_workspace(app: &AppHandle, reference: &WorkspaceRef) -> Result<String, String> {
+    workspace::load_state(app)
+        .workspaces
+        .into_iter()
+        .filter(workspace::is_active)
+        .find(|ws| match reference {
+            WorkspaceRef::Server(id) => ws.server_id == Some(*id),
+            WorkspaceRef::Local(id) => ws.id == *id,
+        })
+        .map(|ws| ws.id)
+        .ok_or_else(|| "Workspace not found".to_string())
+}
+
+fn reject(app: &AppHandle, url: &str, error: String) {
+    let url = oauth::redact_url(url);
+    println!("⚠️ 잘못된 deep link: {} ({})", url, error);
+    if let Err(e) = app.emit("deep-link://invalid", InvalidDeepLink { url, error }) {
+        eprintln!("❌ Deep link emit 실패: {}", e);
+    }
+}
+
+/// deep link 를 파싱해 Rust 쪽 처리(로그인 콜백·창 포커스·워크스페이스 전환)를 하고,
+/// 프론트엔드에 넘길 라우트를 반환. 잘못된 링크는 `deep-link://invalid` 로 알린다.
+fn route(app: &AppHandle, url: &str) -> Option<DeepLink> {
+    let link = match parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            reject(app, url, e);
+            return None;
+        }
+    };
+
+    let link = match link {
+        DeepLink::AuthCallback => {
+            // 로그인 결과는 oauth 가 auth://completed 로 알린다
+            if !oauth::handle_deep_link(app, url) {
+                reject(app, url, "Invalid auth callback".to_string());
+            }
+            return None;
+        }
+        DeepLink::Workspace { workspace_id } => {
+            let switched = resolve_workspace(app, &workspace_id)
+                .and_then(|id| workspace::switch_workspace(app.clone(), id));
+            match switched {
+                Ok(ws) => DeepLink::Workspace {
+                    workspace_id: WorkspaceRef::Local(ws.id),
+                },
+                Err(e) => {
+                    reject(app, url, e);
+                    return None;
+                }
+            }
+        }
+        link => link,
+    };
+
+    focus_main_window(app);
+    if link == DeepLink::Open {
+        return None;
+    }
+    Some(link)
+}
+
+/// deep link 를 처리하고 남은 라우트를 `deep-link://route` 이벤트로 프론트엔드에 보낸다
+fn dispatch(app: &AppHandle, url: &str) {
+    let Some(link) = route(app, url) else {
+        return;
+    };
+    if let Err(e) = app.emit("deep-link://route", &link) {
+        eprintln!("❌ Deep link emit 실패: {}", e);
+    } else {
+        println!("✅ Deep link 라우팅: {:?}", link);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Admission {
+    Deliver,
+    Queued,
+    Duplicate,
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// 프론트엔드가 리스너를 등록했는지. 페이지를 다시 불러오면 false 로 돌아간다.
+    ready: bool,
+    pending: VecDeque<String>,
+    /// 최근에 전달한 링크와 시각 (중복 제거용)
+    recent: VecDeque<(String, Instant)>,
+}
+
+impl QueueState {
+    /// 바로 전달할지, 대기열에 넣을지, 중복이라 버릴지 결정
+    fn admit(&mut self, url: &str, bypass: bool, now: Instant) -> Admission {
+        self.recent
+            .retain(|(_, at)| now.saturating_duration_since(*at) < DEDUP_WINDOW);
+        if self.pending.iter().any(|pending| pending == url)
+            || self.recent.iter().any(|(recent, _)| recent == url)
+        {
+            return Admission::Duplicate;
+        }
+
+        if self.ready || bypass {
+            self.recent.push_back((url.to_string(), now));
+            return Admission::Deliver;
+        }
+
+        if self.pending.len() >= MAX_PENDING {
+            if let Some(dropped) = self.pending.pop_front() {
+                println!(
+                    "⚠️ deep link 대기열이 가득 차 가장 오래된 링크를 버림: {}",
+                    oauth::redact_url(&dropped)
+                );
+            }
+        }
+        self.pending.push_back(url.to_string());
+        Admission::Queued
+    }
+
+    /// 대기 중인 링크를 들어온 순서대로 꺼내고, 이후 링크는 바로 전달하도록 전환
+    fn take_pending(&mut self, now: Instant) -> Vec<String> {
+        self.ready = true;
+        let urls: Vec<String> = self.pending.drain(..).collect();
+        self.recent
+            .extend(urls.iter().map(|url| (url.clone(), now)));
+        urls
+    }
+}
+
+/// 라우팅 스레드가 순서대로 처리하는 작업
+enum Job {
+    Dispatch(String),
+    /// 라우트를 이벤트 대신 응답 채널로 돌려준다
+    Route(Vec<String>, mpsc::Sender<Vec<DeepLink>>),
+}
+
+/// 프론트엔드가 준비되기 전에 들어온 deep link 를 순서대로 보관
+pub struct DeepLinkQueue {
+    state: Mutex<QueueState>,
+    /// 대기열 잠금을 잡은 채로 넣으므로 들어온 순서가 곧 처리 순서
+    jobs: mpsc::Sender<Job>,
+}
+
+impl DeepLinkQueue {
+    /// 라우팅 스레드를 띄운다. 워크스페이스 전환 같은 저장소 I/O 가 deep link 이벤트 스레드를 막지 않는다.
+    pub fn new(app: AppHandle) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in receiver {
+                match job {
+                    Job::Dispatch(url) => dispatch(&app, &url),
+                    Job::Route(urls, reply) => {
+                        let links = urls.iter().filter_map(|url| route(&app, url)).collect();
+                        let _ = reply.send(links);
+                    }
+                }
+            }
+        });
+        Self {
+            state: Mutex::new(QueueState::default()),
+            jobs,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        // 패닉으로 잠금이 오염돼도 대기열 자체는 유효하다
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn submit(&self, job: Job) {
+        if self.jobs.send(job).is_err() {
+            eprintln!("❌ deep link 라우팅 스레드가 종료됨");
+        }
+    }
+}
+
+/// 들어온 deep link 를 처리. 프론트엔드가 아직 준비되지 않았으면 대기열에 넣는다.
+pub fn enqueue(app: &AppHandle, url: &str) {
+    let url = url.trim();
+    let queue = match app.try_state::<DeepLinkQueue>() {
+        Some(queue) => queue,
+        None => return dispatch(app, url),
+    };
+
+    // 로그인 콜백은 기다리는 흐름이 있으므로 대기열을 거치지 않는다
+    let bypass = matches!(parse(url), Ok(DeepLink::AuthCallback));
+    let mut state = queue.lock();
+    match state.admit(url, bypass, Instant::now()) {
+        Admission::Deliver => queue.submit(Job::Dispatch(url.to_string())),
+        Admission::Queued => println!(
+            "⏳ 프론트엔드 준비 전이라 deep link 대기: {}",
+            oauth::redact_url(url)
+        ),
+        Admission::Duplicate => println!("↩️ 중복 deep link 무시: {}", oauth::redact_url(url)),
+    }
+}
+
+/// 웹뷰를 다시 불러오면 리스너가 사라지므로 다음 `frontend_ready` 까지 다시 모은다
+pub fn reset(app: &AppHandle) {
+    if let Some(queue) = app.try_state::<DeepLinkQueue>() {
+        queue.lock().ready = false;
+    }
+}
+
+/// 프론트엔드가 `deep-link://route` 리스너를 등록한 뒤 호출. 대기 중인 링크를 순서대로 보내고 보낸 수를 반환.
+#[tauri::command]
+pub fn frontend_ready(queue: State<'_, DeepLinkQueue>) -> usize {
+    let mut state = queue.lock();
+    let urls = state.take_pending(Instant::now());
+    let count = urls.len();
+    for url in urls {
+        queue.submit(Job::Dispatch(url));
+    }
+    count
+}
+
+/// 대기 중인 링크를 이벤트 대신 라우트 목록으로 반환. 이후 링크는 이벤트로 보낸다.
+#[tauri::command]
+pub async fn drain_pending_deep_links(
+    queue: State<'_, DeepLinkQueue>,
+) -> Result<Vec<DeepLink>, String> {
+    let (reply, links) = mpsc::channel();
+    {
+        let mut state = queue.lock();
+        let urls = state.take_pending(Instant::now());
+        queue.submit(Job::Route(urls, reply));
+    }
+    // 앞서 들어온 링크가 모두 처리된 뒤에 응답이 온다
+    tauri::async_runtime::spawn_blocking(move || links.recv().unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to drain deep links: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        date(y, m, d).and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn open_route() {
+        assert_eq!(parse("deskcal://"), Ok(DeepLink::Open));
+        assert_eq!(parse("deskcal://open"), Ok(DeepLink::Open));
+        assert_eq!(parse("deskcal://open/"), Ok(DeepLink::Open));
+    }
+
+    #[test]
+    fn task_route() {
+        assert_eq!(
+            parse("deskcal://task/42"),
+            Ok(DeepLink::Task { task_id: 42 })
+        );
+        assert_eq!(
+            parse("deskcal://task/42/?from=mail#top"),
+            Ok(DeepLink::Task { task_id: 42 })
+        );
+        assert!(parse("deskcal://task/0").is_err());
+        assert!(parse("deskcal://task/-3").is_err());
+        assert!(parse("deskcal://task/+3").is_err());
+        assert!(parse("deskcal://task/abc").is_err());
+        assert!(parse("deskcal://task").is_err());
+        assert!(parse("deskcal://task/1/2").is_err());
+    }
+
+    #[test]
+    fn workspace_route() {
+        assert_eq!(
+            parse("deskcal://workspace/17"),
+            Ok(DeepLink::Workspace {
+                workspace_id: WorkspaceRef::Server(17)
+            })
+        );
+        assert_eq!(
+            parse("deskcal://workspace/ws_0192a3b4c5d67e8f"),
+            Ok(DeepLink::Workspace {
+                workspace_id: WorkspaceRef::Local("ws_0192a3b4c5d67e8f".to_string())
+            })
+        );
+        assert!(parse("deskcal://workspace/0").is_err());
+        assert!(parse("deskcal://workspace/ws_").is_err());
+        assert!(parse("deskcal://workspace/ws_..%2Fetc").is_err());
+        assert!(parse("deskcal://workspace/team").is_err());
+    }
+
+    #[test]
+    fn date_route() {
+        assert_eq!(
+            parse("deskcal://date/2026-10-17"),
+            Ok(DeepLink::Date {
+                date: date(2026, 10, 17)
+            })
+        );
+        assert!(parse("deskcal://date/2026-02-30").is_err());
+        assert!(parse("deskcal://date/2026-1-5").is_err());
+        assert!(parse("deskcal://date/today").is_err());
+    }
+
+    #[test]
+    fn new_task_route() {
+        assert_eq!(
+            parse("deskcal://new?title=Team+sync%20%F0%9F%93%85&start=2026-10-17T09:30&end=2026-10-17T10:00:00"),
+            Ok(DeepLink::NewTask {
+                title: "Team sync 📅".to_string(),
+                start: Some(time(2026, 10, 17, 9, 30)),
+                end: Some(time(2026, 10, 17, 10, 0)),
+                all_day: false,
+            })
+        );
+        assert_eq!(
+            parse("deskcal://new?title=Trip&start=2026-10-17&end=2026-10-19"),
+            Ok(DeepLink::NewTask {
+                title: "Trip".to_string(),
+                start: Some(time(2026, 10, 17, 0, 0)),
+                end: Some(time(2026, 10, 19, 0, 0)),
+                all_day: true,
+            })
+        );
+        assert_eq!(
+            parse("deskcal://new?title=Inbox&utm_source=web"),
+            Ok(DeepLink::NewTask {
+                title: "Inbox".to_string(),
+                start: None,
+                end: None,
+                all_day: false,
+            })
+        );
+
+        let rfc3339 = "2026-10-17T09:00:00+09:00";
+        let expected = DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Local)
+            .naive_local();
+        match parse(&format!(
+            "deskcal://new?title=Call&start={}",
+            urlencoding::encode(rfc3339)
+        )) {
+            Ok(DeepLink::NewTask { start, .. }) => assert_eq!(start, Some(expected)),
+            other => panic!("unexpected: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn new_task_route_rejects_invalid_parameters() {
+        assert!(parse("deskcal://new").is_err());
+        assert!(parse("deskcal://new?title=+++").is_err());
+        assert!(parse(&format!("deskcal://new?title={}", "a".repeat(201))).is_err());
+        assert!(parse("deskcal://new?title=x&start=tomorrow").is_err());
+        assert!(parse("deskcal://new?title=x&end=2026-10-17T10:00").is_err());
+        assert!(
+            parse("deskcal://new?title=x&start=2026-10-17T10:00&end=2026-10-17T09:00").is_err()
+        );
+        assert!(
+            parse("deskcal://new?title=x&start=2026-10-17T10:00&end=2026-10-17T10:00").is_err()
+        );
+        assert!(parse("deskcal://new?title=x&start=2026-10-17&end=2026-10-17T10:00").is_err());
+        assert!(parse("deskcal://new?title=%FF").is_err());
+    }
+
+    #[test]
+    fn auth_callback_route() {
+        assert_eq!(
+            parse("deskcal://auth/callback?code=abc&state=xyz"),
+            Ok(DeepLink::AuthCallback)
+        );
+        assert_eq!(
+            parse("deskcal://auth/callback/"),
+            Ok(DeepLink::AuthCallback)
+        );
+        assert!(parse("deskcal://auth").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_routes_and_schemes() {
+        assert!(parse("https://example.com/task/1").is_err());
+        assert!(parse("deskcal://settings").is_err());
+        assert!(parse("deskcal:task/1").is_err());
+    }
+
+    #[test]
+    fn serializes_as_tagged_route() {
+        let json = serde_json::to_value(DeepLink::Date {
+            date: date(2026, 10, 17),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "route": "date", "date": "2026-10-17" })
+        );
+        let json = serde_json::to_value(DeepLink::Workspace {
+            workspace_id: WorkspaceRef::Server(5),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "route": "workspace", "workspace_id": 5 })
+        );
+    }
+
+    #[test]
+    fn queue_holds_links_in_order_until_ready() {
+        let now = Instant::now();
+        let mut state = QueueState::default();
+        assert_eq!(
+            state.admit("deskcal://task/1", false, now),
+            Admission::Queued
+        );
+        assert_eq!(
+            state.admit("deskcal://date/2026-10-17", false, now),
+            Admission::Queued
+        );
+        assert_eq!(
+            state.admit("deskcal://task/2", false, now),
+            Admission::Queued
+        );
+        assert_eq!(
+            state.take_pending(now),
+            vec![
+                "deskcal://task/1",
+                "deskcal://date/2026-10-17",
+                "deskcal://task/2"
+            ]
+        );
+        assert!(state.take_pending(now).is_empty());
+        assert_eq!(
+            state.admit("deskcal://task/3", false, now),
+            Admission::Deliver
+        );
+    }
+
+    #[test]
+    fn queue_drops_duplicates_within_window() {
+        let now = Instant::now();
+        let mut state = QueueState::default();
+        assert_eq!(
+            state.admit("deskcal://task/1", false, now),
+            Admission::Queued
+        );
+        assert_eq!(
+            state.admit("deskcal://task/1", false, now),
+            Admission::Duplicate
+        );
+        assert_eq!(state.take_pending(now).len(), 1);
+
+        // 전달 직후 같은 링크가 다시 와도 무시하고, 구간이 지나면 다시 전달
+        assert_eq!(
+            state.admit("deskcal://task/1", false, now),
+            Admission::Duplicate
+        );
+        let later = now + DEDUP_WINDOW;
+        assert_eq!(
+            state.admit("deskcal://task/1", false, later),
+            Admission::Deliver
+        );
+    }
+
+    #[test]
+    fn queue_lets_auth_callbacks_through_and_caps_pending() {
+        let now = Instant::now();
+        let mut state = QueueState::default();
+        let callback = "deskcal://auth/callback?code=a&state=b";
+        assert_eq!(state.admit(callback, true, now), Admission::Deliver);
+        assert!(!state.ready);
+
+        for id in 1..=MAX_PENDING + 2 {
+            state.admit(&format!("deskcal://task/{}", id), false, now);
+        }
+        let pending = state.take_pending(now);
+        assert_eq!(pending.len(), MAX_PENDING);
+        assert_eq!(pending[0], "deskcal://task/3");
+        assert_eq!(
+            pending[MAX_PENDING - 1],
+            format!("deskcal://task/{}", MAX_PENDING + 2)
+        );
+    }
+}
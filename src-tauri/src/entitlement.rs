@@ -0,0 +1,345 @@
+/// Plan entitlements — what the signed-in account may create, fetched from
+/// `/api/me/subscription` and cached locally so limits can still be checked offline.
+///
+/// Limits come from the subscription payload. A limit the server leaves out falls back to
+/// the free plan, whatever the plan is called. A lapsed subscription is treated as the free plan.
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{account, api, storage};
+
+pub const ENTITLEMENTS_KEY: &str = "entitlements";
+const SUBSCRIPTION_ENDPOINT: &str = "/api/me/subscription";
+const CACHE_TTL_SECS: i64 = 60 * 60;
+const FREE_PLAN: &str = "free";
+const GIB: u64 = 1024 * 1024 * 1024;
+const FREE_MAX_PERSONAL_WORKSPACES: u32 = 3;
+const FREE_MAX_TEAM_WORKSPACES: u32 = 0;
+const FREE_TEAM_SEATS: u32 = 1;
+const FREE_STORAGE_QUOTA_BYTES: u64 = GIB;
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServerSubscription {
+    plan_name: String,
+    status: String,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+/// 서버가 보내 주는 한도. 항목이 없으면 무료 플랜 기본값, `null` 이면 무제한
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ServerLimits {
+    #[serde(default, deserialize_with = "present")]
+    max_personal_workspaces: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "present")]
+    max_team_workspaces: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "present")]
+    max_members: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "present")]
+    max_storage_bytes: Option<Option<u64>>,
+    #[serde(default)]
+    storage_used_bytes: Option<u64>,
+}
+
+/// 항목이 있으면 `null` 이라도 `Some` 으로 읽어 "없음" 과 "무제한" 을 구분
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionResponse {
+    #[serde(default)]
+    subscription: Option<ServerSubscription>,
+    #[serde(default)]
+    limits: Option<ServerLimits>,
+}
+
+/// 한도가 `None` 이면 무제한
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entitlements {
+    pub plan_name: String,
+    pub is_paid: bool,
+    #[serde(default)]
+    pub subscription_status: Option<String>,
+    #[serde(default)]
+    pub subscription_expires_at: Option<String>,
+    #[serde(default)]
+    pub max_personal_workspaces: Option<u32>,
+    #[serde(default)]
+    pub max_team_workspaces: Option<u32>,
+    #[serde(default)]
+    pub team_seats: Option<u32>,
+    #[serde(default)]
+    pub storage_quota_bytes: Option<u64>,
+    #[serde(default)]
+    pub storage_used_bytes: Option<u64>,
+    pub fetched_at_unix: i64,
+    pub expires_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntitlementLimit {
+    PersonalWorkspaces,
+    TeamWorkspaces,
+    TeamSeats,
+    StorageQuota,
+}
+
+/// 한도 초과 시 프론트엔드로 보내는 구조화된 에러. 커맨드 에러 문자열에는 JSON 으로 직렬화해 담는다.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpgradeRequired {
+    pub code: String,
+    pub limit: EntitlementLimit,
+    pub plan_name: String,
+    pub current: u64,
+    pub max: u64,
+    pub route: String,
+    pub message: String,
+}
+
+impl UpgradeRequired {
+    pub fn new(limit: EntitlementLimit, entitlements: &Entitlements, current: u64, max: u64) -> Self {
+        let (target, message) = match limit {
+            EntitlementLimit::PersonalWorkspaces => (
+                "workspaces",
+                "현재 플랜에서 만들 수 있는 워크스페이스 수를 모두 사용했습니다.",
+            ),
+            EntitlementLimit::TeamWorkspaces => (
+                "team_workspace",
+                "팀 워크스페이스 생성을 위해 유료 플랜 업그레이드가 필요합니다.",
+            ),
+            EntitlementLimit::TeamSeats => (
+                "team_seats",
+                "현재 플랜의 팀 좌석을 모두 사용했습니다.",
+            ),
+            EntitlementLimit::StorageQuota => (
+                "storage",
+                "저장 공간이 가득 찼습니다. 플랜을 업그레이드해 주세요.",
+            ),
+        };
+        UpgradeRequired {
+            code: "upgrade_required".to_string(),
+            limit,
+            plan_name: entitlements.plan_name.clone(),
+            current,
+            max,
+            route: format!("/billing/upgrade?target={}", target),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn to_error(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.message.clone())
+    }
+}
+
+fn normalize_plan(plan_name: &str) -> String {
+    let normalized = plan_name.trim().to_lowercase();
+    if normalized.is_empty() {
+        FREE_PLAN.to_string()
+    } else {
+        normalized
+    }
+}
+
+fn is_subscription_active(subscription: &ServerSubscription) -> bool {
+    matches!(subscription.status.as_str(), "active" | "trialing")
+}
+
+fn build_entitlements(response: SubscriptionResponse, now: i64) -> Entitlements {
+    let active = response
+        .subscription
+        .as_ref()
+        .filter(|s| is_subscription_active(s));
+    let plan_name = active
+        .map(|s| normalize_plan(&s.plan_name))
+        .unwrap_or_else(|| FREE_PLAN.to_string());
+
+    let server = response.limits.unwrap_or_default();
+    Entitlements {
+        is_paid: plan_name != FREE_PLAN,
+        subscription_status: response.subscription.as_ref().map(|s| s.status.clone()),
+        subscription_expires_at: response.subscription.and_then(|s| s.expires_at),
+        max_personal_workspaces: server
+            .max_personal_workspaces
+            .unwrap_or(Some(FREE_MAX_PERSONAL_WORKSPACES)),
+        max_team_workspaces: server
+            .max_team_workspaces
+            .unwrap_or(Some(FREE_MAX_TEAM_WORKSPACES)),
+        team_seats: server.max_members.unwrap_or(Some(FREE_TEAM_SEATS)),
+        storage_quota_bytes: server
+            .max_storage_bytes
+            .unwrap_or(Some(FREE_STORAGE_QUOTA_BYTES)),
+        storage_used_bytes: server.storage_used_bytes,
+        plan_name,
+        fetched_at_unix: now,
+        expires_at_unix: now + CACHE_TTL_SECS,
+    }
+}
+
+fn free_entitlements(now: i64) -> Entitlements {
+    build_entitlements(
+        SubscriptionResponse {
+            subscription: None,
+            limits: None,
+        },
+        now,
+    )
+}
+
+fn load_cached(app: &tauri::AppHandle) -> Option<Entitlements> {
+    storage::read_json_or_log(app, ENTITLEMENTS_KEY)
+}
+
+async fn fetch(app: &tauri::AppHandle) -> Result<Entitlements, String> {
+    let token = account::access_token(app)?;
+    let response: SubscriptionResponse = api::get_json(SUBSCRIPTION_ENDPOINT, Some(&token)).await?;
+    let entitlements = build_entitlements(response, account::now_unix());
+    storage::write_json(app, ENTITLEMENTS_KEY, &entitlements)?;
+    Ok(entitlements)
+}
+
+/// 캐시가 유효하면 그대로 쓰고, 만료됐으면 서버에서 다시 받는다.
+/// 서버에 닿지 못하면 만료된 캐시라도 사용하고, 캐시가 없으면 무료 플랜으로 간주한다.
+pub async fn current(app: &tauri::AppHandle, force_refresh: bool) -> Entitlements {
+    let now = account::now_unix();
+    let cached = load_cached(app);
+    if let Some(cached) = cached.as_ref() {
+        if !force_refresh && cached.expires_at_unix > now {
+            return cached.clone();
+        }
+    }
+
+    match fetch(app).await {
+        Ok(entitlements) => entitlements,
+        Err(e) => {
+            log::warn!("entitlement: failed to refresh entitlements: {}", e);
+            cached.unwrap_or_else(|| free_entitlements(now))
+        }
+    }
+}
+
+fn check_storage(entitlements: &Entitlements) -> Result<(), UpgradeRequired> {
+    if let (Some(quota), Some(used)) = (entitlements.storage_quota_bytes, entitlements.storage_used_bytes) {
+        if used >= quota {
+            return Err(UpgradeRequired::new(
+                EntitlementLimit::StorageQuota,
+                entitlements,
+                used,
+                quota,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `existing` 는 휴지통을 제외한 개인 워크스페이스 수
+pub fn check_personal_workspace(entitlements: &Entitlements, existing: usize) -> Result<(), UpgradeRequired> {
+    check_storage(entitlements)?;
+    match entitlements.max_personal_workspaces {
+        Some(max) if existing as u64 >= max as u64 => Err(UpgradeRequired::new(
+            EntitlementLimit::PersonalWorkspaces,
+            entitlements,
+            existing as u64,
+            max as u64,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `existing` 는 휴지통을 제외한 팀 워크스페이스 수, `seats_in_use` 는 그 팀들의 멤버 수,
+/// `new_seats` 는 새 팀을 만들면 늘어나는 좌석 수 (만든 사람이 아직 어느 팀에도 없으면 1)
+pub fn check_team_workspace(
+    entitlements: &Entitlements,
+    existing: usize,
+    seats_in_use: usize,
+    new_seats: usize,
+) -> Result<(), UpgradeRequired> {
+    if let Some(seats) = entitlements.team_seats {
+        if (seats_in_use + new_seats) as u64 > seats as u64 {
+            return Err(UpgradeRequired::new(
+                EntitlementLimit::TeamSeats,
+                entitlements,
+                seats_in_use as u64,
+                seats as u64,
+            ));
+        }
+    }
+    check_storage(entitlements)?;
+    match entitlements.max_team_workspaces {
+        Some(max) if existing as u64 >= max as u64 => Err(UpgradeRequired::new(
+            EntitlementLimit::TeamWorkspaces,
+            entitlements,
+            existing as u64,
+            max as u64,
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_entitlements(
+    app: tauri::AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<Entitlements, String> {
+    Ok(current(&app, force_refresh.unwrap_or(false)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(json: &str) -> SubscriptionResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn unknown_plan_without_limits_gets_free_limits() {
+        let entitlements = build_entitlements(
+            response(r#"{"subscription": {"plan_name": "Pr0", "status": "active"}}"#),
+            0,
+        );
+        assert_eq!(entitlements.plan_name, "pr0");
+        assert_eq!(entitlements.max_personal_workspaces, Some(FREE_MAX_PERSONAL_WORKSPACES));
+        assert_eq!(entitlements.max_team_workspaces, Some(FREE_MAX_TEAM_WORKSPACES));
+        assert_eq!(entitlements.team_seats, Some(FREE_TEAM_SEATS));
+        assert!(check_team_workspace(&entitlements, 0, 0, 1).is_err());
+    }
+
+    #[test]
+    fn server_limits_win_and_null_means_unlimited() {
+        let entitlements = build_entitlements(
+            response(
+                r#"{
+                    "subscription": {"plan_name": "team", "status": "active"},
+                    "limits": {"max_personal_workspaces": null, "max_team_workspaces": 5, "max_members": 20}
+                }"#,
+            ),
+            0,
+        );
+        assert_eq!(entitlements.max_personal_workspaces, None);
+        assert_eq!(entitlements.max_team_workspaces, Some(5));
+        assert_eq!(entitlements.team_seats, Some(20));
+        assert_eq!(entitlements.storage_quota_bytes, Some(FREE_STORAGE_QUOTA_BYTES));
+    }
+
+    #[test]
+    fn team_seats_count_only_the_seats_a_new_team_adds() {
+        let mut entitlements = free_entitlements(0);
+        entitlements.max_team_workspaces = None;
+        entitlements.team_seats = Some(1);
+
+        // 이미 한 팀에 있는 사용자는 좌석을 더 쓰지 않으므로 두 번째 팀을 만들 수 있다
+        assert!(check_team_workspace(&entitlements, 1, 1, 0).is_ok());
+        // 아직 팀이 없으면 만든 사람이 마지막 좌석을 차지한다
+        assert!(check_team_workspace(&entitlements, 0, 0, 1).is_ok());
+
+        let upgrade = check_team_workspace(&entitlements, 1, 1, 1).unwrap_err();
+        assert_eq!(upgrade.limit, EntitlementLimit::TeamSeats);
+        assert_eq!((upgrade.current, upgrade.max), (1, 1));
+    }
+}
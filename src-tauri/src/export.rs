@@ -0,0 +1,502 @@
+/// Account data export / import — bundles every locally stored user file into a single
+/// zip with a manifest so it can be restored on another machine.
+///
+/// Documents from the app database are written as `<key>.json` entries. The auth session
+/// is intentionally excluded; tokens never leave the device.
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use crate::storage::{self, config_dir};
+use crate::{account, alarm, position, workspace};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const FORMAT_VERSION: u32 = 1;
+/// 압축 해제 시 파일 하나의 최대 크기 (zip bomb 방지)
+const MAX_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at_unix: i64,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountDataSummary {
+    pub path: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+fn document_keys() -> [&'static str; 5] {
+    [
+        account::ACCOUNT_KEY,
+        account::PREFERENCES_KEY,
+        workspace::WORKSPACE_STATE_KEY,
+        alarm::ALARM_STATE_KEY,
+        position::STATE_KEY,
+    ]
+}
+
+/// 번들 안의 JSON 문서 경로(`<key>.json`)를 저장소 키로 변환
+fn document_key(path: &str) -> Option<&'static str> {
+    let key = path.strip_suffix(".json")?;
+    document_keys().into_iter().find(|k| *k == key)
+}
+
+/// 번들 안의 경로가 허용된 파일인지 확인 (디렉터리 탈출 방지)
+fn is_allowed_entry(path: &str) -> bool {
+    if document_key(path).is_some() {
+        return true;
+    }
+    match path.split_once('/') {
+        Some((dir, name)) => {
+            dir == account::PROFILE_IMAGE_DIR
+                && !name.is_empty()
+                && !name.contains(['/', '\\'])
+                && name != ".."
+                && name != "."
+        }
+        None => false,
+    }
+}
+
+/// 내보낼 파일 목록: (번들 내 경로, 내용). 문서는 한 트랜잭션에서 읽어 일관된 스냅샷을 만든다.
+fn collect_export_files(app: &tauri::AppHandle, dir: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut files: Vec<(String, Vec<u8>)> = storage::transaction(app, |tx| {
+        let mut documents = Vec::new();
+        for key in document_keys() {
+            if let Some(raw) = tx.get_raw(key)? {
+                documents.push((format!("{}.json", key), raw.into_bytes()));
+            }
+        }
+        Ok(documents)
+    })?;
+
+    let profile_dir = dir.join(account::PROFILE_IMAGE_DIR);
+    if profile_dir.is_dir() {
+        let entries = fs::read_dir(&profile_dir)
+            .map_err(|e| format!("Failed to read profile image directory: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                let data = fs::read(&path)
+                    .map_err(|e| format!("Failed to read profile image: {}", e))?;
+                files.push((format!("{}/{}", account::PROFILE_IMAGE_DIR, name), data));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn read_entry<R: Read>(reader: R) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    reader
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to read bundle entry: {}", e))?;
+    if data.len() as u64 > MAX_ENTRY_BYTES {
+        return Err("Bundle entry is too large".to_string());
+    }
+    Ok(data)
+}
+
+/// 번들 전체를 읽고 검증한 뒤 (경로, 내용) 목록을 반환. 검증이 끝나기 전에는 아무것도 쓰지 않는다.
+fn read_and_validate_bundle(source: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let file = File::open(source).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid account data bundle: {}", e))?;
+
+    let manifest: ExportManifest = {
+        let entry = archive
+            .by_name(MANIFEST_FILE)
+            .map_err(|_| "Bundle manifest is missing".to_string())?;
+        let raw = read_entry(entry)?;
+        serde_json::from_slice(&raw).map_err(|e| format!("Invalid bundle manifest: {}", e))?
+    };
+
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported bundle format version: {}",
+            manifest.format_version
+        ));
+    }
+
+    let mut contents = Vec::with_capacity(manifest.files.len());
+    for listed in &manifest.files {
+        if !is_allowed_entry(&listed.path) {
+            return Err(format!("Unexpected file in bundle: {}", listed.path));
+        }
+
+        let entry = archive
+            .by_name(&listed.path)
+            .map_err(|_| format!("Bundle is missing {}", listed.path))?;
+        let data = read_entry(entry)?;
+        if data.len() as u64 != listed.size {
+            return Err(format!("Bundle file {} is corrupted", listed.path));
+        }
+        if listed.path.ends_with(".json") {
+            serde_json::from_slice::<serde_json::Value>(&data)
+                .map_err(|e| format!("Bundle file {} is not valid json: {}", listed.path, e))?;
+        }
+        contents.push((listed.path.clone(), data));
+    }
+
+    Ok(contents)
+}
+
+/// 문서가 아닌 파일(프로필 이미지)을 복원하고, 번들에 없는 이전 계정의 프로필 이미지는 지운다
+fn restore_files(dir: &Path, contents: &[(String, Vec<u8>)]) -> Result<(), String> {
+    for (name, data) in contents {
+        if document_key(name).is_none() {
+            storage::write_file_atomic(&dir.join(name), data)
+                .map_err(|e| format!("Failed to restore {}: {}", name, e))?;
+        }
+    }
+
+    let profile_dir = dir.join(account::PROFILE_IMAGE_DIR);
+    if !profile_dir.is_dir() {
+        return Ok(());
+    }
+    let entries = fs::read_dir(&profile_dir)
+        .map_err(|e| format!("Failed to read profile image directory: {}", e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let bundled = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| format!("{}/{}", account::PROFILE_IMAGE_DIR, n))
+            .is_some_and(|name| contents.iter().any(|(listed, _)| *listed == name));
+        if path.is_file() && !bundled {
+            fs::remove_file(&path).map_err(|e| format!("Failed to delete profile image: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 프로필 이미지 경로는 절대 경로로 저장되므로 새 기기의 설정 디렉터리로 다시 연결
+fn relocate_profile_image(app: &tauri::AppHandle, dir: &Path) -> Result<(), String> {
+    account::update_account_settings(app, |settings| {
+        let file_name = match settings
+            .profile_image_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .and_then(|n| n.to_str())
+        {
+            Some(name) => name.to_string(),
+            None => return Ok(()),
+        };
+
+        let relocated = dir.join(account::PROFILE_IMAGE_DIR).join(file_name);
+        settings.profile_image_path = if relocated.is_file() {
+            Some(relocated.to_string_lossy().to_string())
+        } else {
+            None
+        };
+        Ok(())
+    })
+    .map(|_| ())
+}
+
+/// 파일 목록과 매니페스트를 zip 번들로 쓴다
+fn write_bundle(target: &Path, files: &[(String, Vec<u8>)]) -> Result<ExportManifest, String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let file = File::create(target).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut entries: Vec<ManifestEntry> = Vec::with_capacity(files.len());
+    for (name, data) in files {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        writer
+            .write_all(data)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        entries.push(ManifestEntry {
+            path: name.clone(),
+            size: data.len() as u64,
+        });
+    }
+
+    let manifest = ExportManifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at_unix: account::now_unix(),
+        files: entries,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize json: {}", e))?;
+    writer
+        .start_file(MANIFEST_FILE, options)
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    writer
+        .write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+    Ok(manifest)
+}
+
+#[tauri::command]
+pub fn export_account_data(
+    app: tauri::AppHandle,
+    target_path: String,
+) -> Result<AccountDataSummary, String> {
+    let target = PathBuf::from(target_path.trim());
+    if target.as_os_str().is_empty() {
+        return Err("target_path is required".to_string());
+    }
+
+    let dir = config_dir(&app)?;
+    let files = collect_export_files(&app, &dir)?;
+
+    let manifest = write_bundle(&target, &files)?;
+
+    Ok(AccountDataSummary {
+        path: target.to_string_lossy().to_string(),
+        file_count: manifest.files.len(),
+        total_bytes: manifest.files.iter().map(|f| f.size).sum(),
+    })
+}
+
+#[tauri::command]
+pub fn import_account_data(
+    app: tauri::AppHandle,
+    source_path: String,
+) -> Result<AccountDataSummary, String> {
+    let source = PathBuf::from(source_path.trim());
+    if !source.is_file() {
+        return Err("Account data bundle does not exist".to_string());
+    }
+
+    let contents = read_and_validate_bundle(&source)?;
+    let dir = config_dir(&app)?;
+
+    // 문서는 한 트랜잭션으로 복원해 일부만 적용되는 일이 없도록 한다
+    storage::transaction(&app, |tx| {
+        for (name, data) in &contents {
+            if let Some(key) = document_key(name) {
+                let raw = String::from_utf8_lossy(data);
+                tx.put_raw(key, &raw)?;
+            }
+        }
+        Ok(())
+    })?;
+
+    restore_files(&dir, &contents)?;
+    let total_bytes = contents.iter().map(|(_, data)| data.len() as u64).sum();
+
+    relocate_profile_image(&app, &dir)?;
+    // 이전 버전에서 내보낸 번들이면 id 형식을 바로 맞춘다 (알람을 다시 읽기 전에)
+    workspace::migrate_workspace_ids(&app)?;
+    alarm::reload_alarm_manager(&app)?;
+    workspace::notify_reloaded(&app)?;
+    position::restore_state(&app);
+
+    let summary = AccountDataSummary {
+        path: source.to_string_lossy().to_string(),
+        file_count: contents.len(),
+        total_bytes,
+    };
+    if let Err(e) = app.emit("account://imported", summary.clone()) {
+        log::warn!("export: failed to emit imported event: {}", e);
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("deskcal-export-{}", uuid::Uuid::now_v7().simple()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 매니페스트와 항목을 마음대로 넣은 번들 (검증을 거치지 않는 입력)
+    fn raw_bundle(path: &Path, manifest: &serde_json::Value, entries: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, data) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.start_file(MANIFEST_FILE, options).unwrap();
+        writer.write_all(manifest.to_string().as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn manifest(format_version: u32, files: &[(&str, u64)]) -> serde_json::Value {
+        serde_json::json!({
+            "format_version": format_version,
+            "app_version": "0.0.0",
+            "exported_at_unix": 0,
+            "files": files
+                .iter()
+                .map(|(path, size)| serde_json::json!({ "path": path, "size": size }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let dir = temp_dir();
+        let bundle = dir.join("out").join("bundle.zip");
+        let files = vec![
+            (
+                format!("{}.json", account::ACCOUNT_KEY),
+                br#"{"nickname":"deskcal"}"#.to_vec(),
+            ),
+            (
+                format!("{}.json", workspace::WORKSPACE_STATE_KEY),
+                br#"{"workspaces":[]}"#.to_vec(),
+            ),
+            (
+                format!("{}/avatar.png", account::PROFILE_IMAGE_DIR),
+                vec![0x89, b'P', b'N', b'G', 0, 1, 2],
+            ),
+        ];
+
+        let manifest = write_bundle(&bundle, &files).unwrap();
+        assert_eq!(manifest.format_version, FORMAT_VERSION);
+        assert_eq!(manifest.files.len(), files.len());
+        assert_eq!(read_and_validate_bundle(&bundle).unwrap(), files);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn entry_paths_are_allowlisted() {
+        assert!(is_allowed_entry("account_settings.json"));
+        assert!(is_allowed_entry("window_state.json"));
+        assert!(is_allowed_entry("profile_images/avatar.png"));
+
+        for path in [
+            "auth_session.json",
+            "account_settings",
+            "../account_settings.json",
+            "/etc/passwd",
+            "profile_images/",
+            "profile_images/..",
+            "profile_images/.",
+            "profile_images/../../evil",
+            "profile_images/a/b.png",
+            "profile_images/a\\b.png",
+            "other/avatar.png",
+            "manifest.json",
+        ] {
+            assert!(!is_allowed_entry(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn bundle_with_unlisted_path_is_rejected() {
+        let dir = temp_dir();
+        let bundle = dir.join("bundle.zip");
+        raw_bundle(
+            &bundle,
+            &manifest(FORMAT_VERSION, &[("../evil.json", 2)]),
+            &[("../evil.json", b"{}")],
+        );
+        let error = read_and_validate_bundle(&bundle).unwrap_err();
+        assert!(error.starts_with("Unexpected file in bundle"), "{}", error);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unsupported_format_version_is_rejected() {
+        let dir = temp_dir();
+        let bundle = dir.join("bundle.zip");
+        for version in [0, FORMAT_VERSION + 1] {
+            raw_bundle(&bundle, &manifest(version, &[]), &[]);
+            let error = read_and_validate_bundle(&bundle).unwrap_err();
+            assert_eq!(
+                error,
+                format!("Unsupported bundle format version: {}", version)
+            );
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn size_mismatch_and_invalid_json_are_rejected() {
+        let dir = temp_dir();
+        let bundle = dir.join("bundle.zip");
+        raw_bundle(
+            &bundle,
+            &manifest(FORMAT_VERSION, &[("window_state.json", 99)]),
+            &[("window_state.json", b"{}")],
+        );
+        assert!(read_and_validate_bundle(&bundle)
+            .unwrap_err()
+            .ends_with("is corrupted"));
+
+        raw_bundle(
+            &bundle,
+            &manifest(FORMAT_VERSION, &[("window_state.json", 5)]),
+            &[("window_state.json", b"{oops")],
+        );
+        assert!(read_and_validate_bundle(&bundle)
+            .unwrap_err()
+            .contains("is not valid json"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn entries_over_the_size_cap_are_rejected() {
+        let at_cap = std::io::repeat(0).take(MAX_ENTRY_BYTES);
+        assert_eq!(read_entry(at_cap).unwrap().len() as u64, MAX_ENTRY_BYTES);
+
+        let over_cap = std::io::repeat(0).take(MAX_ENTRY_BYTES + 1);
+        assert_eq!(
+            read_entry(over_cap).unwrap_err(),
+            "Bundle entry is too large"
+        );
+    }
+
+    #[test]
+    fn import_replaces_previous_profile_images() {
+        let dir = temp_dir();
+        let profile_dir = dir.join(account::PROFILE_IMAGE_DIR);
+        fs::create_dir_all(&profile_dir).unwrap();
+        fs::write(profile_dir.join("old.png"), b"old").unwrap();
+        fs::write(profile_dir.join("kept.png"), b"stale").unwrap();
+
+        let contents = vec![
+            (format!("{}.json", account::ACCOUNT_KEY), b"{}".to_vec()),
+            (
+                format!("{}/kept.png", account::PROFILE_IMAGE_DIR),
+                b"new".to_vec(),
+            ),
+        ];
+        restore_files(&dir, &contents).unwrap();
+
+        assert!(!profile_dir.join("old.png").exists());
+        assert_eq!(fs::read(profile_dir.join("kept.png")).unwrap(), b"new");
+        // 문서는 DB 로 가므로 파일로 쓰지 않는다
+        assert!(!dir.join(format!("{}.json", account::ACCOUNT_KEY)).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,489 @@
+/// OIDC ID token verification — signature against the provider's JWKS, then issuer,
+/// audience, expiry and nonce.
+///
+/// JWKS documents are cached per `jwks_uri`. A token signed with a key id that is not in
+/// the cache triggers one refetch, so providers can rotate keys without an app restart.
+/// Only RS256 and ES256 are accepted; `none` and HMAC algorithms are always rejected.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::api;
+
+const JWKS_TTL_SECS: i64 = 60 * 60;
+/// 모르는 kid 때문에 JWKS 를 다시 받는 최소 간격 (위조 토큰으로 요청을 남발하지 못하게)
+const JWKS_MIN_REFETCH_SECS: i64 = 60;
+/// 클라이언트/공급자 시계 차이 허용 범위
+const CLOCK_SKEW_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default)]
+    pub kid: Option<String>,
+    #[serde(default)]
+    pub alg: Option<String>,
+    #[serde(default, rename = "use")]
+    pub key_use: Option<String>,
+    #[serde(default)]
+    pub n: Option<String>,
+    #[serde(default)]
+    pub e: Option<String>,
+    #[serde(default)]
+    pub crv: Option<String>,
+    #[serde(default)]
+    pub x: Option<String>,
+    #[serde(default)]
+    pub y: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Jwks {
+    #[serde(default)]
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwks {
+    fn has_key(&self, kid: Option<&str>) -> bool {
+        match kid {
+            Some(kid) => self.keys.iter().any(|k| k.kid.as_deref() == Some(kid)),
+            None => !self.keys.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+/// `aud` 는 문자열 하나이거나 배열
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn values(&self) -> Vec<&str> {
+        match self {
+            Audience::One(aud) => vec![aud.as_str()],
+            Audience::Many(auds) => auds.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    aud: Audience,
+    pub exp: i64,
+    #[serde(default)]
+    pub iat: Option<i64>,
+    #[serde(default)]
+    pub nbf: Option<i64>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub azp: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// 표시 이름 (Kakao 는 `nickname`, Google 은 `name`)
+    pub fn display_name(&self) -> Option<String> {
+        self.nickname
+            .clone()
+            .or_else(|| self.name.clone())
+            .or_else(|| self.preferred_username.clone())
+    }
+
+    /// 공급자가 검증하지 않았다고 밝힌 이메일은 쓰지 않는다
+    pub fn verified_email(&self) -> Option<String> {
+        match self.email_verified {
+            Some(false) => None,
+            _ => self.email.clone(),
+        }
+    }
+}
+
+/// 토큰이 만족해야 하는 값
+pub struct Expected<'a> {
+    /// 허용하는 issuer (Google 은 scheme 없는 값도 보낸다)
+    pub issuers: &'a [String],
+    pub client_id: &'a str,
+    pub nonce: &'a str,
+    pub now: i64,
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|_| "Malformed ID token".to_string())
+}
+
+fn split_token(token: &str) -> Result<(&str, &str, &str), String> {
+    let mut parts = token.split('.');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(payload), Some(signature), None)
+            if !header.is_empty() && !payload.is_empty() && !signature.is_empty() =>
+        {
+            Ok((header, payload, signature))
+        }
+        _ => Err("Malformed ID token".to_string()),
+    }
+}
+
+fn parse_header(token: &str) -> Result<JwtHeader, String> {
+    let (header, _, _) = split_token(token)?;
+    serde_json::from_slice(&decode_segment(header)?)
+        .map_err(|_| "Malformed ID token header".to_string())
+}
+
+/// 헤더의 kid / alg 에 맞는 서명 키. kid 가 없으면 종류가 맞는 키가 하나뿐일 때만 사용한다.
+fn select_key<'a>(jwks: &'a Jwks, header: &JwtHeader, kty: &str) -> Result<&'a Jwk, String> {
+    let candidates: Vec<&Jwk> = jwks
+        .keys
+        .iter()
+        .filter(|k| k.kty == kty)
+        .filter(|k| k.key_use.as_deref().map_or(true, |u| u == "sig"))
+        .filter(|k| k.alg.as_deref().map_or(true, |alg| alg == header.alg))
+        .filter(|k| header.kid.is_none() || k.kid == header.kid)
+        .collect();
+    match candidates.as_slice() {
+        [key] => Ok(key),
+        [] => Err("No matching signing key for ID token".to_string()),
+        _ => Err("Ambiguous signing key for ID token".to_string()),
+    }
+}
+
+fn verify_signature(
+    jwks: &Jwks,
+    header: &JwtHeader,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), String> {
+    match header.alg.as_str() {
+        "RS256" => {
+            let key = select_key(jwks, header, "RSA")?;
+            let n = decode_segment(key.n.as_deref().ok_or("RSA key is missing n")?)?;
+            let e = decode_segment(key.e.as_deref().ok_or("RSA key is missing e")?)?;
+            RsaPublicKeyComponents { n: &n, e: &e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .map_err(|_| "Invalid ID token signature".to_string())
+        }
+        "ES256" => {
+            let key = select_key(jwks, header, "EC")?;
+            if key.crv.as_deref() != Some("P-256") {
+                return Err("Unsupported EC curve".to_string());
+            }
+            let x = decode_segment(key.x.as_deref().ok_or("EC key is missing x")?)?;
+            let y = decode_segment(key.y.as_deref().ok_or("EC key is missing y")?)?;
+            // SEC1 비압축 점: 0x04 || x || y
+            let mut point = Vec::with_capacity(1 + x.len() + y.len());
+            point.push(0x04);
+            point.extend_from_slice(&x);
+            point.extend_from_slice(&y);
+            UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                .verify(message, signature)
+                .map_err(|_| "Invalid ID token signature".to_string())
+        }
+        other => Err(format!("Unsupported ID token algorithm: {}", other)),
+    }
+}
+
+fn validate_claims(claims: &IdTokenClaims, expected: &Expected) -> Result<(), String> {
+    if !expected.issuers.iter().any(|iss| iss == &claims.iss) {
+        return Err("ID token issuer mismatch".to_string());
+    }
+
+    // OIDC Core §3.1.3.7: aud 에 client id 가 있어야 하고, 여러 개면 azp 가 client id 여야 한다
+    let audiences = claims.aud.values();
+    if !audiences.contains(&expected.client_id) {
+        return Err("ID token audience mismatch".to_string());
+    }
+    if audiences.len() > 1 && claims.azp.as_deref() != Some(expected.client_id) {
+        return Err("ID token authorized party mismatch".to_string());
+    }
+    if let Some(azp) = claims.azp.as_deref() {
+        if azp != expected.client_id {
+            return Err("ID token authorized party mismatch".to_string());
+        }
+    }
+
+    if claims.exp + CLOCK_SKEW_SECS <= expected.now {
+        return Err("ID token has expired".to_string());
+    }
+    if claims
+        .nbf
+        .is_some_and(|nbf| nbf - CLOCK_SKEW_SECS > expected.now)
+        || claims
+            .iat
+            .is_some_and(|iat| iat - CLOCK_SKEW_SECS > expected.now)
+    {
+        return Err("ID token is not valid yet".to_string());
+    }
+
+    match claims.nonce.as_deref() {
+        Some(nonce) if crate::oauth::constant_time_eq(nonce, expected.nonce) => Ok(()),
+        _ => Err("ID token nonce mismatch".to_string()),
+    }
+}
+
+/// 서명과 클레임을 모두 검증하고 클레임을 반환
+pub fn verify_id_token(
+    token: &str,
+    jwks: &Jwks,
+    expected: &Expected,
+) -> Result<IdTokenClaims, String> {
+    let (header_segment, payload_segment, signature_segment) = split_token(token)?;
+    let header = parse_header(token)?;
+    let signature = decode_segment(signature_segment)?;
+    let message = &token[..header_segment.len() + 1 + payload_segment.len()];
+    verify_signature(jwks, &header, message.as_bytes(), &signature)?;
+
+    let claims: IdTokenClaims = serde_json::from_slice(&decode_segment(payload_segment)?)
+        .map_err(|e| format!("Failed to parse ID token claims: {}", e))?;
+    validate_claims(&claims, expected)?;
+    Ok(claims)
+}
+
+/// jwks_uri → (JWKS, 받은 시각). setup 에서 앱 상태로 등록한다.
+#[derive(Default)]
+pub struct JwksCache {
+    entries: Mutex<HashMap<String, (Jwks, i64)>>,
+}
+
+impl JwksCache {
+    fn cached(&self, jwks_uri: &str) -> Option<(Jwks, i64)> {
+        self.entries.lock().ok()?.get(jwks_uri).cloned()
+    }
+
+    async fn fetch(&self, jwks_uri: &str, now: i64) -> Result<Jwks, String> {
+        let jwks: Jwks = api::get_json_external(jwks_uri, None)
+            .await
+            .map_err(|e| format!("Failed to fetch JWKS: {}", e))?;
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(jwks_uri.to_string(), (jwks.clone(), now));
+        }
+        Ok(jwks)
+    }
+
+    /// 캐시된 JWKS 로 검증. 캐시가 만료됐거나 토큰의 kid 가 없으면(키 교체) 다시 받는다.
+    pub async fn verify(
+        &self,
+        jwks_uri: &str,
+        token: &str,
+        expected: &Expected<'_>,
+    ) -> Result<IdTokenClaims, String> {
+        let header = parse_header(token)?;
+        let jwks = match self.cached(jwks_uri) {
+            Some((jwks, fetched_at))
+                if expected.now - fetched_at < JWKS_TTL_SECS
+                    && (jwks.has_key(header.kid.as_deref())
+                        || expected.now - fetched_at < JWKS_MIN_REFETCH_SECS) =>
+            {
+                jwks
+            }
+            _ => self.fetch(jwks_uri, expected.now).await?,
+        };
+        verify_id_token(token, &jwks, expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+    use ring::rand::SystemRandom;
+    use ring::signature::RsaKeyPair;
+
+    const JWKS_FIXTURE: &str = include_str!("../tests/fixtures/oidc_jwks.json");
+    const SIGNING_KEY_FIXTURE: &str = include_str!("../tests/fixtures/oidc_signing_key.pem");
+    const ISSUER: &str = "https://issuer.example.com";
+    const CLIENT_ID: &str = "deskcal-desktop";
+    const NONCE: &str = "n-0S6_WzA2Mj";
+    const NOW: i64 = 1_800_000_000;
+
+    fn jwks() -> Jwks {
+        serde_json::from_str(JWKS_FIXTURE).unwrap()
+    }
+
+    fn sign(header: serde_json::Value, claims: serde_json::Value) -> String {
+        let pem: String = SIGNING_KEY_FIXTURE
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let key = RsaKeyPair::from_pkcs8(&STANDARD.decode(pem).unwrap()).unwrap();
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let mut signature = vec![0u8; key.public().modulus_len()];
+        key.sign(
+            &signature::RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            message.as_bytes(),
+            &mut signature,
+        )
+        .unwrap();
+        format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    fn header() -> serde_json::Value {
+        serde_json::json!({ "alg": "RS256", "kid": "deskcal-test-1", "typ": "JWT" })
+    }
+
+    fn claims() -> serde_json::Value {
+        serde_json::json!({
+            "iss": ISSUER,
+            "sub": "1234567890",
+            "aud": CLIENT_ID,
+            "exp": NOW + 3600,
+            "iat": NOW,
+            "nonce": NONCE,
+            "email": "user@example.com",
+            "email_verified": true,
+            "nickname": "데스크캘",
+        })
+    }
+
+    fn verify(token: &str) -> Result<IdTokenClaims, String> {
+        let issuers = vec![ISSUER.to_string()];
+        let expected = Expected {
+            issuers: &issuers,
+            client_id: CLIENT_ID,
+            nonce: NONCE,
+            now: NOW,
+        };
+        verify_id_token(token, &jwks(), &expected)
+    }
+
+    fn with(field: &str, value: serde_json::Value) -> serde_json::Value {
+        let mut claims = claims();
+        claims[field] = value;
+        claims
+    }
+
+    #[test]
+    fn accepts_valid_token() {
+        let claims = verify(&sign(header(), claims())).unwrap();
+        assert_eq!(claims.sub, "1234567890");
+        assert_eq!(claims.verified_email().as_deref(), Some("user@example.com"));
+        assert_eq!(claims.display_name().as_deref(), Some("데스크캘"));
+    }
+
+    #[test]
+    fn rejects_tampered_payload() {
+        let token = sign(header(), claims());
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let forged = URL_SAFE_NO_PAD.encode(with("sub", "attacker".into()).to_string());
+        parts[1] = &forged;
+        assert_eq!(
+            verify(&parts.join(".")).unwrap_err(),
+            "Invalid ID token signature"
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_issuer_audience_and_nonce() {
+        let cases = [
+            (
+                "iss",
+                serde_json::json!("https://evil.example.com"),
+                "ID token issuer mismatch",
+            ),
+            (
+                "aud",
+                serde_json::json!("other-client"),
+                "ID token audience mismatch",
+            ),
+            (
+                "aud",
+                serde_json::json!([CLIENT_ID, "other-client"]),
+                "ID token authorized party mismatch",
+            ),
+            (
+                "nonce",
+                serde_json::json!("replayed"),
+                "ID token nonce mismatch",
+            ),
+        ];
+        for (field, value, error) in cases {
+            assert_eq!(
+                verify(&sign(header(), with(field, value))).unwrap_err(),
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_missing_nonce() {
+        let mut claims = claims();
+        claims.as_object_mut().unwrap().remove("nonce");
+        assert_eq!(
+            verify(&sign(header(), claims)).unwrap_err(),
+            "ID token nonce mismatch"
+        );
+    }
+
+    #[test]
+    fn rejects_expired_and_future_tokens() {
+        let expired = with("exp", (NOW - CLOCK_SKEW_SECS - 1).into());
+        assert_eq!(
+            verify(&sign(header(), expired)).unwrap_err(),
+            "ID token has expired"
+        );
+        let future = with("nbf", (NOW + CLOCK_SKEW_SECS + 1).into());
+        assert_eq!(
+            verify(&sign(header(), future)).unwrap_err(),
+            "ID token is not valid yet"
+        );
+        // 허용 범위 안의 시계 차이는 통과
+        assert!(verify(&sign(header(), with("exp", (NOW - 10).into()))).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_key_and_algorithms() {
+        let rotated = serde_json::json!({ "alg": "RS256", "kid": "rotated-key" });
+        assert_eq!(
+            verify(&sign(rotated, claims())).unwrap_err(),
+            "No matching signing key for ID token"
+        );
+
+        let none = format!(
+            "{}.{}.sig",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(claims().to_string())
+        );
+        assert_eq!(
+            verify(&none).unwrap_err(),
+            "Unsupported ID token algorithm: none"
+        );
+        assert_eq!(verify("not-a-jwt").unwrap_err(), "Malformed ID token");
+    }
+
+    #[test]
+    fn kid_lookup_reports_rotation() {
+        let jwks = jwks();
+        assert!(jwks.has_key(Some("deskcal-test-1")));
+        assert!(!jwks.has_key(Some("rotated-key")));
+    }
+}
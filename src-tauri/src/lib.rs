@@ -1,14 +1,26 @@
 mod account;
 mod alarm;
+mod api;
+mod deep_link;
 mod desktop_attach;
+mod entitlement;
+mod export;
+mod id_token;
 mod oauth;
+mod oauth_page;
+mod oauth_provider;
 mod position;
+mod storage;
 mod workspace;
+mod workspace_sync;
+mod workspace_team;
+mod workspace_template;
 
 #[cfg(target_os = "windows")]
 mod autostart;
 
-use tauri::{Emitter, Manager};
+use tauri::webview::PageLoadEvent;
+use tauri::Manager;
 use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -26,6 +38,14 @@ pub fn run() {
             autostart::set_autostart,
             autostart::get_autostart,
             oauth::start_oauth_server,
+            oauth::cancel_oauth_flow,
+            oauth::get_oauth_flow_status,
+            oauth::start_provider_login,
+            oauth_provider::list_oauth_providers,
+            oauth_provider::add_oidc_provider,
+            oauth_provider::remove_oidc_provider,
+            deep_link::frontend_ready,
+            deep_link::drain_pending_deep_links,
             account::get_account_settings,
             account::set_reserved_nicknames,
             account::is_nickname_available,
@@ -38,6 +58,8 @@ pub fn run() {
             account::save_auth_session,
             account::clear_auth_session,
             account::logout,
+            export::export_account_data,
+            export::import_account_data,
             alarm::set_alarm_notifications_enabled,
             alarm::get_alarm_manager_state,
             alarm::clear_workspace_task_alarms,
@@ -45,15 +67,43 @@ pub fn run() {
             alarm::snooze_alarm,
             alarm::dismiss_alarm,
             workspace::get_workspace_state,
+            workspace::get_workspace_snapshot,
             workspace::list_personal_workspaces,
             workspace::list_team_workspaces,
             workspace::get_current_workspace,
             workspace::switch_workspace,
             workspace::create_workspace,
+            workspace_template::duplicate_workspace,
             workspace::rename_workspace,
             workspace::delete_workspace,
+            workspace::restore_workspace,
+            workspace::purge_workspace,
+            workspace::archive_workspace,
+            workspace::unarchive_workspace,
+            workspace::list_trashed_workspaces,
+            workspace::set_trash_retention_days,
+            workspace::reorder_workspaces,
+            workspace::set_workspace_pinned,
+            workspace::set_launch_workspace,
+            workspace::create_workspace_group,
+            workspace::rename_workspace_group,
+            workspace::delete_workspace_group,
+            workspace::reorder_workspace_groups,
+            workspace::move_workspace_to_group,
+            workspace::update_workspace_settings,
+            workspace::get_workspace_time_zone,
+            entitlement::get_entitlements,
             workspace::get_team_workspace_creation_path,
             workspace::create_team_workspace,
+            workspace_sync::sync_workspaces,
+            workspace_sync::list_workspace_conflicts,
+            workspace_sync::resolve_workspace_conflict,
+            workspace_team::list_team_members,
+            workspace_team::invite_team_member,
+            workspace_template::list_workspace_templates,
+            workspace_template::save_workspace_template,
+            workspace_template::delete_workspace_template,
+            workspace_template::create_workspace_from_template,
             desktop_attach::toggle_desktop_mode,
             desktop_attach::is_desktop_mode,
             desktop_attach::set_desktop_mode,
@@ -69,6 +119,14 @@ pub fn run() {
             position::save_window_opacity,
             position::get_window_opacity,
             oauth::start_oauth_server,
+            oauth::cancel_oauth_flow,
+            oauth::get_oauth_flow_status,
+            oauth::start_provider_login,
+            oauth_provider::list_oauth_providers,
+            oauth_provider::add_oidc_provider,
+            oauth_provider::remove_oidc_provider,
+            deep_link::frontend_ready,
+            deep_link::drain_pending_deep_links,
             account::get_account_settings,
             account::set_reserved_nicknames,
             account::is_nickname_available,
@@ -81,6 +139,8 @@ pub fn run() {
             account::save_auth_session,
             account::clear_auth_session,
             account::logout,
+            export::export_account_data,
+            export::import_account_data,
             alarm::set_alarm_notifications_enabled,
             alarm::get_alarm_manager_state,
             alarm::clear_workspace_task_alarms,
@@ -88,15 +148,43 @@ pub fn run() {
             alarm::snooze_alarm,
             alarm::dismiss_alarm,
             workspace::get_workspace_state,
+            workspace::get_workspace_snapshot,
             workspace::list_personal_workspaces,
             workspace::list_team_workspaces,
             workspace::get_current_workspace,
             workspace::switch_workspace,
             workspace::create_workspace,
+            workspace_template::duplicate_workspace,
             workspace::rename_workspace,
             workspace::delete_workspace,
+            workspace::restore_workspace,
+            workspace::purge_workspace,
+            workspace::archive_workspace,
+            workspace::unarchive_workspace,
+            workspace::list_trashed_workspaces,
+            workspace::set_trash_retention_days,
+            workspace::reorder_workspaces,
+            workspace::set_workspace_pinned,
+            workspace::set_launch_workspace,
+            workspace::create_workspace_group,
+            workspace::rename_workspace_group,
+            workspace::delete_workspace_group,
+            workspace::reorder_workspace_groups,
+            workspace::move_workspace_to_group,
+            workspace::update_workspace_settings,
+            workspace::get_workspace_time_zone,
+            entitlement::get_entitlements,
             workspace::get_team_workspace_creation_path,
             workspace::create_team_workspace,
+            workspace_sync::sync_workspaces,
+            workspace_sync::list_workspace_conflicts,
+            workspace_sync::resolve_workspace_conflict,
+            workspace_team::list_team_members,
+            workspace_team::invite_team_member,
+            workspace_template::list_workspace_templates,
+            workspace_template::save_workspace_template,
+            workspace_template::delete_workspace_template,
+            workspace_template::create_workspace_from_template,
             desktop_attach::toggle_desktop_mode,
             desktop_attach::is_desktop_mode,
             desktop_attach::set_desktop_mode,
@@ -117,27 +205,18 @@ pub fn run() {
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
             println!("═══════════════════════════════════════════════════════════");
             println!("🔁 SINGLE INSTANCE: 두 번째 인스턴스 감지!");
-            println!("   argv: {:?}", argv);
+            let redacted_argv: Vec<String> = argv.iter().map(|a| oauth::redact_url(a)).collect();
+            println!("   argv: {:?}", redacted_argv);
             println!("═══════════════════════════════════════════════════════════");
 
             // Windows/Linux에서 deep link URL은 argv에 포함됨
             // argv = ["C:\path\app.exe", "deskcal://auth/callback?token=..."]
             for arg in argv.iter() {
                 if arg.starts_with("deskcal://") {
-                    println!("🔗 Deep link URL 발견: {}", arg);
+                    println!("🔗 Deep link URL 발견: {}", oauth::redact_url(arg));
 
-                    // 프론트엔드가 준비될 때까지 짧은 딜레이
-                    let handle = app.clone();
-                    let url = arg.clone();
-                    std::thread::spawn(move || {
-                        std::thread::sleep(std::time::Duration::from_millis(100));
-                        // 기존 인스턴스의 프론트엔드로 이벤트 전송
-                        if let Err(e) = handle.emit("deep-link://new-url", url) {
-                            eprintln!("❌ Deep link emit 실패: {}", e);
-                        } else {
-                            println!("✅ Deep link 이벤트 전송 완료");
-                        }
-                    });
+                    // 기존 인스턴스에서 라우팅 (프론트엔드가 준비 전이면 대기열에 보관)
+                    deep_link::enqueue(app, arg);
                 }
             }
 
@@ -157,9 +236,23 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .on_page_load(|webview, payload| {
+            // 새로고침하면 리스너가 사라지므로 다시 frontend_ready 를 기다린다
+            if webview.label() == "main" && matches!(payload.event(), PageLoadEvent::Started) {
+                deep_link::reset(webview.app_handle());
+            }
+        })
         .setup(|app| {
-            let alarm_state = alarm::load_alarm_manager(app.handle());
+            storage::init(app.handle())?;
+            workspace::migrate_workspace_ids(app.handle())?;
+            workspace::apply_launch_workspace(app.handle())?;
+
+            let alarm_state = alarm::load_alarm_manager(app.handle())?;
             app.manage(std::sync::Mutex::new(alarm_state));
+            app.manage(oauth::OAuthFlowRegistry::default());
+            app.manage(oauth_provider::OAuthProviderRegistry::default());
+            app.manage(id_token::JwksCache::default());
+            app.manage(deep_link::DeepLinkQueue::new(app.handle().clone()));
 
             // Logging
             app.handle().plugin(
@@ -193,11 +286,8 @@ pub fn run() {
             app.deep_link().on_open_url(move |event| {
                 if let Some(url) = event.urls().first() {
                     let url_str = url.to_string();
-                    println!("🔗 Deep link 수신 (on_open_url): {}", url_str);
-
-                    if let Err(e) = handle.emit("deep-link://new-url", url_str) {
-                        eprintln!("❌ Deep link emit 실패: {}", e);
-                    }
+                    println!("🔗 Deep link 수신 (on_open_url): {}", oauth::redact_url(&url_str));
+                    deep_link::enqueue(&handle, &url_str);
                 }
             });
 
@@ -261,6 +351,9 @@ pub fn run() {
                     tauri::WindowEvent::Resized(size) => {
                         position::save_size(&app_handle, size.width as f64, size.height as f64);
                     }
+                    tauri::WindowEvent::CloseRequested { .. } => {
+                        position::flush_pending_bounds(&app_handle);
+                    }
                     _ => {}
                 });
             }
@@ -270,29 +363,28 @@ pub fn run() {
             // (앱이 실행 중이지 않을 때 deep link 클릭)
             // ═══════════════════════════════════════════════════════════
             let args: Vec<String> = std::env::args().collect();
-            println!("🚀 앱 시작 인자: {:?}", args);
+            let redacted_args: Vec<String> = args.iter().map(|a| oauth::redact_url(a)).collect();
+            println!("🚀 앱 시작 인자: {:?}", redacted_args);
 
             for arg in args.iter().skip(1) {
                 if arg.starts_with("deskcal://") {
-                    println!("🔗 시작 인자에서 deep link 발견: {}", arg);
-                    let handle = app.handle().clone();
-                    let url = arg.clone();
-
-                    // 프론트엔드가 준비될 때까지 대기
-                    std::thread::spawn(move || {
-                        std::thread::sleep(std::time::Duration::from_millis(1500));
-                        println!("🔗 시작 deep link 전송: {}", url);
-                        if let Err(e) = handle.emit("deep-link://new-url", url) {
-                            eprintln!("❌ 시작 deep link emit 실패: {}", e);
-                        }
-                    });
+                    println!("🔗 시작 인자에서 deep link 발견: {}", oauth::redact_url(arg));
+                    // 프론트엔드가 frontend_ready 를 호출할 때 전달된다
+                    deep_link::enqueue(app.handle(), arg);
                 }
             }
 
             alarm::start_alarm_scheduler(app.handle().clone());
+            account::start_session_watchdog(app.handle().clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 디바운스 중인 창 위치 / 크기가 종료와 함께 사라지지 않도록 저장
+            if let tauri::RunEvent::Exit = event {
+                position::flush_pending_bounds(app);
+            }
+        });
 }
@@ -1,8 +1,35 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::net::TcpListener;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_shell::ShellExt;
+use uuid::Uuid;
+
+use crate::account::{self, AuthSession};
+use crate::api;
+use crate::id_token::{Expected, IdTokenClaims, JwksCache};
+use crate::oauth_page;
+use crate::oauth_provider::{self, OAuthProviderRegistry, ResolvedProvider};
+
+/// 인가 코드를 토큰으로 교환하는 엔드포인트 (PKCE)
+const TOKEN_ENDPOINT: &str = "/api/auth/external/token";
+const READ_TIMEOUT_SECS: u64 = 5;
+/// 요청 줄 + 헤더 최대 크기. 큰 쿠키가 붙어도 충분하도록 넉넉하게 잡는다.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+/// 동시에 처리할 최대 연결 수 (브라우저의 favicon / preconnect 포함)
+const MAX_CONNECTIONS: usize = 16;
+const FLOW_TIMEOUT_SECS: u64 = 300;
+/// 서버가 로그인 후 앱을 다시 여는 deep link 콜백
+pub const DEEP_LINK_CALLBACK: &str = "deskcal://auth/callback";
+/// 끝난 흐름은 상태 조회를 위해 잠시 남겨 둔다
+const FINISHED_FLOW_RETENTION_SECS: i64 = 600;
 
 /// OAuth 콜백 데이터
 #[derive(Clone, serde::Serialize)]
@@ -13,47 +40,700 @@ pub struct OAuthCallback {
     pub member_id: Option<String>,
     pub nickname: Option<String>,
     pub email: Option<String>,
+    pub expires_at_unix: Option<i64>,
+    pub error: Option<String>,
+    /// 공급자가 보낸 `error_description` (사용자에게 보여 줄 설명)
+    pub error_description: Option<String>,
+}
+
+/// 로그인 시작 정보. `authorize_url` 은 서버의 로그인 시작 엔드포인트로, 응답의 `authUrl` 을 브라우저로 연다.
+#[derive(Clone, serde::Serialize)]
+pub struct OAuthFlowStart {
+    /// deep link 로 돌아오는 흐름은 로컬 서버가 없으므로 0
+    pub port: u16,
+    pub redirect_uri: String,
+    pub authorize_url: String,
+    pub state: String,
+    pub flow_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthFlowStatus {
+    /// 브라우저에서 로그인 대기 중
+    Waiting,
+    /// 콜백을 받아 토큰을 교환하는 중
+    Exchanging,
+    Completed,
+    Failed,
+    Cancelled,
+    TimedOut,
+}
+
+impl OAuthFlowStatus {
+    fn is_finished(self) -> bool {
+        !matches!(self, OAuthFlowStatus::Waiting | OAuthFlowStatus::Exchanging)
+    }
+}
+
+/// 로그인 후 브라우저가 앱으로 돌아오는 경로
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthRedirect {
+    /// `http://127.0.0.1:<port>/callback` 로컬 서버
+    #[default]
+    Loopback,
+    /// `deskcal://auth/callback` deep link
+    DeepLink,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OAuthFlowInfo {
+    pub flow_id: String,
+    pub provider: String,
+    pub redirect: OAuthRedirect,
+    pub port: u16,
+    pub status: OAuthFlowStatus,
+    pub started_at_unix: i64,
+    pub finished_at_unix: Option<i64>,
+}
+
+/// `oauth://timeout`, `oauth://cancelled` 페이로드
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OAuthFlowEvent {
+    pub flow_id: String,
+    pub provider: String,
+}
+
+/// `auth://completed` 페이로드. 토큰은 담지 않는다 — 세션은 이미 저장돼 있으므로
+/// 프론트엔드는 `get_auth_session` 으로 읽는다.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuthCompletedEvent {
+    pub source: OAuthRedirect,
+    pub flow_id: String,
+    pub success: bool,
+    pub provider: Option<String>,
+    pub member_id: Option<String>,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
     pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// 진행 중이거나 최근에 끝난 로그인 흐름 (flow_id → 흐름). setup 에서 앱 상태로 등록한다.
+#[derive(Default)]
+pub struct OAuthFlowRegistry {
+    flows: Mutex<HashMap<String, Arc<FlowContext>>>,
+}
+
+/// 인가 코드를 어디서 토큰으로 바꾸는지
+enum TokenExchange {
+    /// DeskCal 서버가 공급자 로그인을 대행 (`/api/auth/{provider}/start`)
+    Server,
+    /// 앱이 공급자 토큰 엔드포인트와 직접 교환. OIDC 면 ID token 의 nonce 를 확인한다.
+    Provider {
+        provider: Box<ResolvedProvider>,
+        nonce: String,
+    },
+}
+
+/// 로그인 대기 중인 로컬 서버의 공유 상태
+struct FlowContext {
+    app: AppHandle,
+    flow_id: String,
+    provider: String,
+    redirect: OAuthRedirect,
+    port: u16,
+    state: String,
+    verifier: String,
+    redirect_uri: String,
+    exchange: TokenExchange,
+    started_at_unix: i64,
+    status: Mutex<(OAuthFlowStatus, Option<i64>)>,
+    should_stop: AtomicBool,
+    active_connections: AtomicUsize,
+}
+
+impl FlowContext {
+    fn status(&self) -> OAuthFlowStatus {
+        self.status
+            .lock()
+            .map(|guard| guard.0)
+            .unwrap_or(OAuthFlowStatus::Failed)
+    }
+
+    /// 현재 상태가 `from` 중 하나일 때만 `to` 로 바꾼다. 바뀌었으면 true.
+    fn transition(&self, from: &[OAuthFlowStatus], to: OAuthFlowStatus) -> bool {
+        let mut guard = match self.status.lock() {
+            Ok(guard) => guard,
+            Err(_) => return false,
+        };
+        if !from.contains(&guard.0) {
+            return false;
+        }
+        guard.0 = to;
+        if to.is_finished() {
+            guard.1 = Some(account::now_unix());
+            self.should_stop.store(true, Ordering::Relaxed);
+        }
+        true
+    }
+
+    fn info(&self) -> OAuthFlowInfo {
+        let (status, finished_at_unix) = self
+            .status
+            .lock()
+            .map(|guard| *guard)
+            .unwrap_or((OAuthFlowStatus::Failed, None));
+        OAuthFlowInfo {
+            flow_id: self.flow_id.clone(),
+            provider: self.provider.clone(),
+            redirect: self.redirect,
+            port: self.port,
+            status,
+            started_at_unix: self.started_at_unix,
+            finished_at_unix,
+        }
+    }
+
+    fn emit_flow_event(&self, event: &str) {
+        let payload = OAuthFlowEvent {
+            flow_id: self.flow_id.clone(),
+            provider: self.provider.clone(),
+        };
+        if let Err(e) = self.app.emit(event, payload) {
+            println!("❌ 이벤트 전송 실패: {}", e);
+        }
+    }
+
+    /// 대기 중인 흐름 취소. 토큰 교환 중이면 결과를 버린다.
+    fn cancel(&self) -> bool {
+        let cancelled = self.transition(
+            &[OAuthFlowStatus::Waiting, OAuthFlowStatus::Exchanging],
+            OAuthFlowStatus::Cancelled,
+        );
+        if cancelled {
+            println!("🛑 OAuth 흐름 취소: {}", self.flow_id);
+            self.emit_flow_event("oauth://cancelled");
+        }
+        cancelled
+    }
+
+    /// 시간이 지났으면 TimedOut 으로 바꾸고 `oauth://timeout` 전송. 토큰 교환 중이면 끝날 때까지 기다린다.
+    fn check_timeout(&self, started: std::time::Instant) -> bool {
+        let expired = started.elapsed() > std::time::Duration::from_secs(FLOW_TIMEOUT_SECS);
+        if expired && self.transition(&[OAuthFlowStatus::Waiting], OAuthFlowStatus::TimedOut) {
+            println!("⏰ OAuth 흐름 타임아웃: {}", self.flow_id);
+            self.emit_flow_event("oauth://timeout");
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: String,
+    /// 이름은 소문자로 저장
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum HttpError {
+    Malformed,
+    TooLarge,
+    Timeout,
+    Io,
+}
+
+/// 콜백 쿼리 — 토큰 대신 일회용 인가 코드만 받는다
+#[derive(Debug, Default)]
+struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct TokenRequest<'a> {
+    grant_type: &'a str,
+    code: &'a str,
+    code_verifier: &'a str,
+    redirect_uri: &'a str,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    member_id: Option<serde_json::Value>,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    /// access token 유효 시간 (초)
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// RFC 6749 §5.1 토큰 응답
+#[derive(serde::Deserialize)]
+struct ProviderTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    id_token: Option<String>,
+}
+
+/// OIDC userinfo 중 사용하는 항목 (Google 은 `name`, Kakao 는 `nickname`)
+#[derive(serde::Deserialize)]
+struct UserInfo {
+    sub: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// 로그에 남기면 안 되는 쿼리 파라미터 (소문자 비교)
+const SENSITIVE_PARAMS: [&str; 4] = ["code", "state", "code_verifier", "id_token"];
+
+/// URL 이나 요청 줄의 토큰·코드 값을 가린 사본. 로그에는 항상 이 값을 출력한다.
+pub fn redact_url(url: &str) -> String {
+    let (base, rest) = match url.find(['?', '#']) {
+        Some(index) => url.split_at(index),
+        None => return url.to_string(),
+    };
+
+    let mut redacted = base.to_string();
+    let mut remaining = rest;
+    while let Some(separator) = remaining.chars().next() {
+        redacted.push(separator);
+        remaining = &remaining[separator.len_utf8()..];
+        let end = remaining.find(['&', '#', ' ']).unwrap_or(remaining.len());
+        let (param, tail) = remaining.split_at(end);
+        match param.split_once('=') {
+            Some((key, _)) if is_sensitive_param(key) => {
+                redacted.push_str(key);
+                redacted.push_str("=[REDACTED]");
+            }
+            _ => redacted.push_str(param),
+        }
+        // 요청 줄의 ` HTTP/1.1` 같은 나머지는 그대로 둔다
+        if tail.starts_with(' ') {
+            redacted.push_str(tail);
+            break;
+        }
+        remaining = tail;
+    }
+    redacted
+}
+
+fn is_sensitive_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.contains("token") || SENSITIVE_PARAMS.contains(&key.as_str())
 }
 
-/// 로컬 HTTP 서버를 시작하고 OAuth 콜백 URL을 반환
+/// 토큰은 HTTPS 로만 받는다 (개발용 localhost 서버는 예외)
+fn ensure_secure_transport() -> Result<(), String> {
+    let base = api::base_url();
+    let secure = base.starts_with("https://")
+        || base.starts_with("http://localhost")
+        || base.starts_with("http://127.0.0.1");
+    if secure {
+        Ok(())
+    } else {
+        Err("Refusing to exchange tokens over an insecure connection".to_string())
+    }
+}
+
+/// PKCE verifier / state 용 난수 (base64url, 패딩 없음)
+fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    URL_SAFE_NO_PAD.encode(buf)
+}
+
+/// RFC 7636 S256: BASE64URL(SHA256(verifier))
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// 길이가 같으면 끝까지 비교해 타이밍으로 state 를 추측할 수 없게 한다
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn error_callback(message: &str) -> OAuthCallback {
+    OAuthCallback {
+        provider: None,
+        access_token: None,
+        refresh_token: None,
+        member_id: None,
+        nickname: None,
+        email: None,
+        expires_at_unix: None,
+        error: Some(message.to_string()),
+        error_description: None,
+    }
+}
+
+/// 인가 코드 + verifier 를 서버에 보내 토큰을 받는다
+async fn exchange_code(
+    code: &str,
+    verifier: &str,
+    redirect_uri: &str,
+) -> Result<OAuthCallback, String> {
+    ensure_secure_transport()?;
+    let request = TokenRequest {
+        grant_type: "authorization_code",
+        code,
+        code_verifier: verifier,
+        redirect_uri,
+    };
+    let tokens: TokenResponse = api::post_json(TOKEN_ENDPOINT, None, &request).await?;
+    Ok(OAuthCallback {
+        provider: tokens.provider,
+        access_token: Some(tokens.access_token),
+        refresh_token: tokens.refresh_token,
+        member_id: tokens.member_id.map(|id| match id {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        }),
+        nickname: tokens.nickname,
+        email: tokens.email,
+        expires_at_unix: tokens.expires_in.map(|secs| account::now_unix() + secs),
+        error: None,
+        error_description: None,
+    })
+}
+
+/// 공급자 JWKS 로 ID token 검증 (JWKS 는 앱 상태에 캐시)
+async fn verify_provider_id_token(
+    app: &AppHandle,
+    provider: &ResolvedProvider,
+    id_token: &str,
+    nonce: &str,
+) -> Result<IdTokenClaims, String> {
+    let jwks_uri = provider
+        .jwks_uri
+        .as_deref()
+        .ok_or_else(|| "OAuth provider has no JWKS uri".to_string())?;
+    oauth_provider::ensure_https("JWKS uri", jwks_uri)?;
+    let cache = app
+        .try_state::<JwksCache>()
+        .ok_or_else(|| "JWKS cache is not initialized".to_string())?;
+    let issuers = provider.accepted_issuers();
+    let expected = Expected {
+        issuers: &issuers,
+        client_id: &provider.client_id,
+        nonce,
+        now: account::now_unix(),
+    };
+    cache.verify(jwks_uri, id_token, &expected).await
+}
+
+/// 공급자 토큰 엔드포인트와 직접 교환. OIDC 공급자는 검증한 ID token 클레임으로,
+/// 그 밖의 공급자는 userinfo 로 사용자 정보를 채운다.
+async fn exchange_with_provider(
+    app: &AppHandle,
+    provider: &ResolvedProvider,
+    nonce: &str,
+    code: &str,
+    verifier: &str,
+    redirect_uri: &str,
+) -> Result<OAuthCallback, String> {
+    oauth_provider::ensure_https("Token endpoint", &provider.token_endpoint)?;
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("code_verifier", verifier),
+        ("redirect_uri", redirect_uri),
+        ("client_id", provider.client_id.as_str()),
+    ];
+    let tokens: ProviderTokenResponse =
+        api::post_form_external(&provider.token_endpoint, &form).await?;
+
+    let (member_id, nickname, email) = if provider.is_oidc() {
+        let id_token = tokens
+            .id_token
+            .as_deref()
+            .ok_or_else(|| "OAuth provider did not return an ID token".to_string())?;
+        let claims = verify_provider_id_token(app, provider, id_token, nonce).await?;
+        (
+            Some(claims.sub.clone()),
+            claims.display_name(),
+            claims.verified_email(),
+        )
+    } else {
+        // 사용자 정보는 없어도 로그인 자체는 성공으로 본다
+        let user = match provider.userinfo_endpoint.as_deref() {
+            Some(endpoint) => {
+                api::get_json_external::<UserInfo>(endpoint, Some(&tokens.access_token))
+                    .await
+                    .map_err(|e| println!("⚠️ userinfo 조회 실패: {}", e))
+                    .ok()
+            }
+            None => None,
+        };
+        match user {
+            Some(user) => (Some(user.sub), user.nickname.or(user.name), user.email),
+            None => (None, None, None),
+        }
+    };
+
+    Ok(OAuthCallback {
+        provider: Some(provider.id.clone()),
+        access_token: Some(tokens.access_token),
+        refresh_token: tokens.refresh_token,
+        member_id,
+        nickname,
+        email,
+        expires_at_unix: tokens.expires_in.map(|secs| account::now_unix() + secs),
+        error: None,
+        error_description: None,
+    })
+}
+
+async fn exchange(context: &FlowContext, code: &str) -> Result<OAuthCallback, String> {
+    match &context.exchange {
+        TokenExchange::Server => {
+            exchange_code(code, &context.verifier, &context.redirect_uri).await
+        }
+        TokenExchange::Provider { provider, nonce } => {
+            exchange_with_provider(
+                &context.app,
+                provider,
+                nonce,
+                code,
+                &context.verifier,
+                &context.redirect_uri,
+            )
+            .await
+        }
+    }
+}
+
+fn validate_provider_id(provider: &str) -> Result<String, String> {
+    let provider = provider.trim().to_lowercase();
+    if provider.is_empty() || !provider.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Invalid OAuth provider".to_string());
+    }
+    Ok(provider)
+}
+
+/// PKCE + state 가 포함된 DeskCal 서버 로그인 URL 을 반환. 기본은 로컬 HTTP 서버로 돌아오고,
+/// `redirect` 가 `deep_link` 면 `deskcal://auth/callback` 으로 돌아온다.
+/// 이전 로그인 흐름이 남아 있으면 먼저 취소한다.
 #[tauri::command]
-pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
-    // 사용 가능한 포트 찾기
-    let listener = TcpListener::bind("127.0.0.1:0")
-        .map_err(|e| format!("Failed to bind to localhost: {}", e))?;
+pub async fn start_oauth_server(
+    app: AppHandle,
+    registry: State<'_, OAuthFlowRegistry>,
+    provider: String,
+    redirect: Option<OAuthRedirect>,
+) -> Result<OAuthFlowStart, String> {
+    let provider = validate_provider_id(&provider)?;
+    let start_url = api::url(&format!("/api/auth/{}/start", provider));
+    begin_flow(
+        app,
+        &registry,
+        provider,
+        redirect.unwrap_or_default(),
+        TokenExchange::Server,
+        |redirect_uri, state, challenge| {
+            format!(
+                "{}?callback={}&state={}&code_challenge={}&code_challenge_method=S256",
+                start_url,
+                urlencoding::encode(redirect_uri),
+                state,
+                challenge,
+            )
+        },
+    )
+}
 
-    let port = listener
-        .local_addr()
-        .map_err(|e| format!("Failed to get local address: {}", e))?
-        .port();
+/// 등록된 공급자로 직접 로그인: 로컬 서버를 띄우고 시스템 브라우저로 인가 페이지를 연다.
+/// 콜백이 오면 앱이 공급자와 토큰을 교환하고 세션을 저장한 뒤 `auth://completed` 를 보낸다.
+#[tauri::command]
+pub async fn start_provider_login(
+    app: AppHandle,
+    registry: State<'_, OAuthFlowRegistry>,
+    providers: State<'_, OAuthProviderRegistry>,
+    provider: String,
+) -> Result<OAuthFlowStart, String> {
+    let provider = validate_provider_id(&provider)?;
+    let resolved = providers.resolve(&app, &provider).await?;
+    oauth_provider::ensure_https("Authorization endpoint", &resolved.authorize_endpoint)?;
 
-    println!("🚀 OAuth 서버 시작: http://127.0.0.1:{}", port);
+    let nonce = random_token(16);
+    let authorize = resolved.clone();
+    let authorize_nonce = nonce.clone();
+    let flow = begin_flow(
+        app.clone(),
+        &registry,
+        provider,
+        OAuthRedirect::Loopback,
+        TokenExchange::Provider {
+            provider: Box::new(resolved),
+            nonce,
+        },
+        |redirect_uri, state, challenge| {
+            authorize.authorize_url(redirect_uri, state, challenge, &authorize_nonce)
+        },
+    )?;
 
-    // 서버 종료 플래그
-    let should_stop = Arc::new(AtomicBool::new(false));
-    let should_stop_clone = should_stop.clone();
+    // opener 플러그인으로 옮기기 전까지는 shell 플러그인으로 연다
+    #[allow(deprecated)]
+    let opened = app.shell().open(&flow.authorize_url, None);
+    if let Err(e) = opened {
+        if let Some(context) = registry
+            .flows
+            .lock()
+            .ok()
+            .and_then(|f| f.get(&flow.flow_id).cloned())
+        {
+            context.cancel();
+        }
+        return Err(format!("Failed to open browser: {}", e));
+    }
+    Ok(flow)
+}
+
+/// 이전 흐름을 취소하고 새 흐름을 등록한다 (루프백이면 로컬 서버도 띄운다).
+/// `build_url` 은 (redirect_uri, state, code_challenge) 로 브라우저에 열 URL 을 만든다.
+fn begin_flow(
+    app: AppHandle,
+    registry: &OAuthFlowRegistry,
+    provider: String,
+    redirect: OAuthRedirect,
+    exchange: TokenExchange,
+    build_url: impl FnOnce(&str, &str, &str) -> String,
+) -> Result<OAuthFlowStart, String> {
+    {
+        let mut flows = registry
+            .flows
+            .lock()
+            .map_err(|_| "Failed to lock OAuth flows".to_string())?;
+        for flow in flows.values() {
+            flow.cancel();
+        }
+        let now = account::now_unix();
+        flows.retain(|_, flow| {
+            !matches!(
+                flow.info().finished_at_unix,
+                Some(finished) if now - finished >= FINISHED_FLOW_RETENTION_SECS
+            )
+        });
+    }
+
+    let (listener, port, redirect_uri) = match redirect {
+        OAuthRedirect::Loopback => {
+            // 사용 가능한 포트 찾기
+            let listener = TcpListener::bind("127.0.0.1:0")
+                .map_err(|e| format!("Failed to bind to localhost: {}", e))?;
+            let port = listener
+                .local_addr()
+                .map_err(|e| format!("Failed to get local address: {}", e))?
+                .port();
+            println!("🚀 OAuth 서버 시작: http://127.0.0.1:{}", port);
+            let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+            (Some(listener), port, redirect_uri)
+        }
+        OAuthRedirect::DeepLink => (None, 0, DEEP_LINK_CALLBACK.to_string()),
+    };
+
+    let state = random_token(32);
+    let verifier = random_token(32);
+    let authorize_url = build_url(&redirect_uri, &state, &code_challenge(&verifier));
+    let flow_id = format!("oauth_{}", Uuid::now_v7().simple());
+    let flow = OAuthFlowStart {
+        port,
+        redirect_uri: redirect_uri.clone(),
+        authorize_url,
+        state: state.clone(),
+        flow_id: flow_id.clone(),
+    };
+
+    let context = Arc::new(FlowContext {
+        app,
+        flow_id: flow_id.clone(),
+        provider,
+        redirect,
+        port,
+        state,
+        verifier,
+        redirect_uri,
+        exchange,
+        started_at_unix: account::now_unix(),
+        status: Mutex::new((OAuthFlowStatus::Waiting, None)),
+        should_stop: AtomicBool::new(false),
+        active_connections: AtomicUsize::new(0),
+    });
+    registry
+        .flows
+        .lock()
+        .map_err(|_| "Failed to lock OAuth flows".to_string())?
+        .insert(flow_id, context.clone());
+
+    let listener = match listener {
+        Some(listener) => listener,
+        None => {
+            // deep link 흐름은 콜백을 기다리며 타임아웃만 감시
+            std::thread::spawn(move || {
+                let started = std::time::Instant::now();
+                while !context.should_stop.load(Ordering::Relaxed)
+                    && !context.check_timeout(started)
+                {
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                }
+            });
+            return Ok(flow);
+        }
+    };
 
     // 논블로킹 모드로 설정 (타임아웃 처리용)
     listener
         .set_nonblocking(true)
         .map_err(|e| format!("Failed to set non-blocking: {}", e))?;
 
-    // 별도 스레드에서 콜백 대기
+    // 별도 스레드에서 콜백 대기. 연결마다 스레드를 띄워 favicon 요청 등이 콜백을 막지 않게 한다.
     std::thread::spawn(move || {
         let start_time = std::time::Instant::now();
-        let timeout = std::time::Duration::from_secs(300); // 5분 타임아웃
 
         loop {
-            // 타임아웃 체크
-            if start_time.elapsed() > timeout {
-                println!("⏰ OAuth 서버 타임아웃");
+            // 종료 플래그 체크 (완료 / 실패 / 취소)
+            if context.should_stop.load(Ordering::Relaxed) {
+                println!("🛑 OAuth 서버 종료 요청");
                 break;
             }
 
-            // 종료 플래그 체크
-            if should_stop_clone.load(Ordering::Relaxed) {
-                println!("🛑 OAuth 서버 종료 요청");
+            if context.check_timeout(start_time) {
                 break;
             }
 
@@ -61,44 +741,17 @@ pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
             match listener.accept() {
                 Ok((mut stream, addr)) => {
                     println!("📥 연결 수신: {}", addr);
-
-                    // 요청 읽기
-                    let mut buffer = [0; 8192];
-                    stream.set_nonblocking(false).ok();
-
-                    if let Ok(n) = stream.read(&mut buffer) {
-                        let request = String::from_utf8_lossy(&buffer[..n]);
-                        println!("📄 요청:\n{}", request.lines().next().unwrap_or(""));
-
-                        // 콜백 데이터 파싱
-                        if let Some(callback) = parse_oauth_callback(&request) {
-                            // 브라우저에 응답 전송
-                            let response = create_html_response(&callback);
-                            let _ = stream.write_all(response.as_bytes());
-                            let _ = stream.flush();
-
-                            // 앱에 이벤트 전송
-                            if let Err(e) = app.emit("oauth://callback", callback.clone()) {
-                                println!("❌ 이벤트 전송 실패: {}", e);
-                            } else {
-                                println!("✅ OAuth 콜백 이벤트 전송 완료");
-                            }
-
-                            // 메인 윈도우 포커스
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.unminimize();
-                                let _ = window.set_focus();
-                                let _ = window.show();
-                            }
-
-                            // 성공적으로 처리됨, 서버 종료
-                            break;
-                        } else {
-                            // 잘못된 요청 (favicon 등)
-                            let response = "HTTP/1.1 404 Not Found\r\n\r\n";
-                            let _ = stream.write_all(response.as_bytes());
-                        }
+                    if context.active_connections.load(Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        write_response(&mut stream, "503 Service Unavailable", &[], "");
+                        continue;
                     }
+
+                    context.active_connections.fetch_add(1, Ordering::SeqCst);
+                    let context = context.clone();
+                    std::thread::spawn(move || {
+                        handle_connection(stream, &context);
+                        context.active_connections.fetch_sub(1, Ordering::SeqCst);
+                    });
                 }
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     // 연결 없음, 잠시 대기 후 재시도
@@ -106,6 +759,7 @@ pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
                 }
                 Err(e) => {
                     println!("❌ Accept 에러: {}", e);
+                    context.transition(&[OAuthFlowStatus::Waiting], OAuthFlowStatus::Failed);
                     break;
                 }
             }
@@ -114,153 +768,631 @@ pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
         println!("🔚 OAuth 서버 종료");
     });
 
-    Ok(port)
+    Ok(flow)
+}
+
+/// 요청 헤더를 끝(`\r\n\r\n`)까지 읽어 파싱. 본문은 사용하지 않는다.
+fn read_request(stream: &mut impl Read) -> Result<HttpRequest, HttpError> {
+    let mut buffer: Vec<u8> = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        let n = stream.read(&mut chunk).map_err(|e| match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => HttpError::Timeout,
+            _ => HttpError::Io,
+        })?;
+        if n == 0 {
+            return Err(HttpError::Malformed);
+        }
+        // 직전 청크 끝에 걸친 구분자도 찾을 수 있도록 3바이트 앞부터 검색
+        let search_from = buffer.len().saturating_sub(3);
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buffer[search_from..]
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+        {
+            break search_from + pos;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err(HttpError::TooLarge);
+        }
+    };
+    if head_end > MAX_HEADER_BYTES {
+        return Err(HttpError::TooLarge);
+    }
+
+    let head = std::str::from_utf8(&buffer[..head_end]).map_err(|_| HttpError::Malformed)?;
+    parse_request_head(head)
+}
+
+fn parse_request_head(head: &str) -> Result<HttpRequest, HttpError> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().ok_or(HttpError::Malformed)?;
+    let mut parts = request_line.split(' ');
+    let (method, target, version) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(m), Some(t), Some(v), None) if !m.is_empty() && !t.is_empty() => (m, t, v),
+        _ => return Err(HttpError::Malformed),
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::Malformed);
+    }
+
+    let mut headers = Vec::new();
+    for line in lines {
+        // obs-fold(줄 이어쓰기)는 RFC 7230 에서 폐기됨 — 거부
+        if line.starts_with([' ', '\t']) {
+            return Err(HttpError::Malformed);
+        }
+        let (name, value) = line.split_once(':').ok_or(HttpError::Malformed)?;
+        if name.is_empty() || name.contains([' ', '\t']) {
+            return Err(HttpError::Malformed);
+        }
+        headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    // origin-form 만 허용 (`/path?query`)
+    if !target.starts_with('/') {
+        return Err(HttpError::Malformed);
+    }
+    let target = target.split('#').next().unwrap_or(target);
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (target, ""),
+    };
+
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers,
+    })
+}
+
+/// 콜백이 아닌 요청에 돌려줄 상태와 헤더. 경로는 `/callback` 과 정확히 같아야 한다
+fn reject_non_callback(
+    request: &HttpRequest,
+) -> Option<(&'static str, &'static [(&'static str, &'static str)])> {
+    if request.method != "GET" {
+        return Some(("405 Method Not Allowed", &[("Allow", "GET")]));
+    }
+    if request.path != "/callback" {
+        // 잘못된 요청 (favicon 등)
+        return Some(("404 Not Found", &[]));
+    }
+    None
+}
+
+/// 항상 `Connection: close` 로 응답 — keep-alive 는 지원하지 않는다
+fn write_response(stream: &mut impl Write, status: &str, headers: &[(&str, &str)], body: &str) {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+fn handle_connection(mut stream: TcpStream, context: &FlowContext) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(READ_TIMEOUT_SECS)));
+    let _ = stream.set_write_timeout(Some(std::time::Duration::from_secs(READ_TIMEOUT_SECS)));
+
+    let request = match read_request(&mut stream) {
+        Ok(request) => request,
+        Err(HttpError::TooLarge) => {
+            write_response(&mut stream, "431 Request Header Fields Too Large", &[], "");
+            return;
+        }
+        Err(HttpError::Malformed) => {
+            write_response(&mut stream, "400 Bad Request", &[], "");
+            return;
+        }
+        Err(HttpError::Timeout) => {
+            write_response(&mut stream, "408 Request Timeout", &[], "");
+            return;
+        }
+        Err(HttpError::Io) => return,
+    };
+    println!(
+        "📄 요청: {} {}",
+        request.method,
+        redact_url(&format!("{}?{}", request.path, request.query))
+    );
+
+    if let Some((status, headers)) = reject_non_callback(&request) {
+        write_response(&mut stream, status, headers, "");
+        return;
+    }
+    if let Some(agent) = request.header("user-agent") {
+        log::debug!("oauth: callback user-agent: {}", agent);
+    }
+
+    // 콜백 데이터 파싱
+    let params = match parse_oauth_callback(&request.query) {
+        Some(params) => params,
+        None => {
+            write_response(&mut stream, "400 Bad Request", &[], "");
+            return;
+        }
+    };
+
+    // state 가 다르면 다른 프로세스/페이지가 보낸 요청 — 거부하고 계속 대기
+    let state_ok = params
+        .state
+        .as_deref()
+        .is_some_and(|s| constant_time_eq(s, &context.state));
+    if !state_ok {
+        println!("⚠️ OAuth state 불일치, 요청 거부");
+        write_response(&mut stream, "400 Bad Request", &[], "");
+        return;
+    }
+
+    let callback = match complete_callback(context, params) {
+        Some(callback) => callback,
+        None => {
+            write_response(&mut stream, "409 Conflict", &[], "");
+            return;
+        }
+    };
+
+    // 브라우저에 응답 전송
+    let html = oauth_page::render_result_page(&callback, &account::load_preferences(&context.app));
+    write_response(
+        &mut stream,
+        "200 OK",
+        &[("Content-Type", "text/html; charset=utf-8")],
+        &html,
+    );
 }
 
-/// HTTP 요청에서 OAuth 콜백 파라미터 파싱
-fn parse_oauth_callback(request: &str) -> Option<OAuthCallback> {
-    // GET /callback?accessToken=...&refreshToken=... HTTP/1.1
-    let first_line = request.lines().next()?;
-    let path = first_line.split_whitespace().nth(1)?;
+fn session_from_callback(callback: &OAuthCallback) -> AuthSession {
+    AuthSession {
+        provider: callback.provider.clone(),
+        access_token: callback.access_token.clone(),
+        refresh_token: callback.refresh_token.clone(),
+        member_id: callback.member_id.clone(),
+        nickname: callback.nickname.clone(),
+        email: callback.email.clone(),
+        expires_at_unix: callback.expires_at_unix,
+    }
+}
 
-    // /callback 경로 확인
-    if !path.starts_with("/callback") {
+fn emit_auth_completed(context: &FlowContext, callback: &OAuthCallback) {
+    let payload = AuthCompletedEvent {
+        source: context.redirect,
+        flow_id: context.flow_id.clone(),
+        success: callback.error.is_none(),
+        provider: callback.provider.clone(),
+        member_id: callback.member_id.clone(),
+        nickname: callback.nickname.clone(),
+        email: callback.email.clone(),
+        error: callback.error.clone(),
+        error_description: callback.error_description.clone(),
+    };
+    if let Err(e) = context.app.emit("auth://completed", payload) {
+        println!("❌ 이벤트 전송 실패: {}", e);
+    } else {
+        println!("✅ auth://completed 이벤트 전송 완료");
+    }
+}
+
+/// 루프백 서버와 deep link 가 함께 쓰는 콜백 처리: 토큰 교환 → 세션 저장 → `auth://completed`.
+/// state 는 호출하는 쪽에서 확인한다. 이미 처리됐거나 취소된 흐름이면 None.
+fn complete_callback(context: &FlowContext, params: CallbackParams) -> Option<OAuthCallback> {
+    // 동시에 들어온 콜백 중 첫 번째만 처리 (인가 코드는 한 번만 쓸 수 있음). 취소된 흐름도 거부.
+    if !context.transition(&[OAuthFlowStatus::Waiting], OAuthFlowStatus::Exchanging) {
         return None;
     }
 
-    // 쿼리 스트링 파싱
-    let query = path.split('?').nth(1).unwrap_or("");
+    let mut callback = match (params.error, params.code) {
+        (Some(error), _) => OAuthCallback {
+            error_description: params.error_description,
+            ..error_callback(&error)
+        },
+        (None, Some(code)) => tauri::async_runtime::block_on(exchange(context, &code))
+            .unwrap_or_else(|e| {
+                println!("❌ 토큰 교환 실패: {}", e);
+                error_callback("token_exchange_failed")
+            }),
+        (None, None) => error_callback("missing_code"),
+    };
 
-    let mut callback = OAuthCallback {
-        provider: None,
-        access_token: None,
-        refresh_token: None,
-        member_id: None,
-        nickname: None,
-        email: None,
-        error: None,
+    let final_status = if callback.error.is_some() {
+        OAuthFlowStatus::Failed
+    } else {
+        OAuthFlowStatus::Completed
     };
+    if !context.transition(&[OAuthFlowStatus::Exchanging], final_status) {
+        // 교환 중에 취소됨 — 받은 토큰은 저장하지 않는다
+        return None;
+    }
+
+    if callback.error.is_none() {
+        if let Err(e) = account::save_session(&context.app, &session_from_callback(&callback)) {
+            println!("❌ 세션 저장 실패: {}", e);
+            context.transition(&[OAuthFlowStatus::Completed], OAuthFlowStatus::Failed);
+            callback = error_callback("session_save_failed");
+        }
+    }
+
+    emit_auth_completed(context, &callback);
+
+    // 메인 윈도우 포커스
+    if let Some(window) = context.app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        let _ = window.show();
+    }
+    Some(callback)
+}
+
+/// `deskcal://auth/callback` 이면 처리를 시작하고 true. 다른 deep link 는 false.
+pub fn handle_deep_link(app: &AppHandle, url: &str) -> bool {
+    let rest = match url.strip_prefix(DEEP_LINK_CALLBACK) {
+        Some(rest) => rest.strip_prefix('/').unwrap_or(rest),
+        None => return false,
+    };
+    if !(rest.is_empty() || rest.starts_with(['?', '#'])) {
+        return false;
+    }
+    let query = rest
+        .strip_prefix('?')
+        .unwrap_or("")
+        .split('#')
+        .next()
+        .unwrap_or("")
+        .to_string();
+
+    // 토큰 교환은 네트워크를 타므로 deep link 이벤트 스레드를 막지 않는다
+    let app = app.clone();
+    std::thread::spawn(move || handle_deep_link_callback(&app, &query));
+    true
+}
+
+/// 등록된 deep link 흐름과 state 가 맞을 때만 처리한다. 맞지 않는 콜백은 아무 웹 페이지나
+/// 보낼 수 있으므로, 진행 중인 로그인을 실패로 만들지 않도록 로그만 남기고 버린다.
+fn handle_deep_link_callback(app: &AppHandle, query: &str) {
+    let rejected = |error: &str| {
+        println!("⚠️ deep link 로그인 콜백 무시: {}", error);
+    };
+
+    let params = match parse_oauth_callback(query) {
+        Some(params) => params,
+        None => return rejected("invalid_callback"),
+    };
+    let state = match params.state.as_deref() {
+        Some(state) => state,
+        None => return rejected("invalid_state"),
+    };
+    let context = app.try_state::<OAuthFlowRegistry>().and_then(|registry| {
+        let flows = registry.flows.lock().ok()?;
+        flows
+            .values()
+            .find(|flow| {
+                flow.redirect == OAuthRedirect::DeepLink && constant_time_eq(state, &flow.state)
+            })
+            .cloned()
+    });
+    match context {
+        Some(context) => {
+            if complete_callback(&context, params).is_none() {
+                println!("⚠️ 이미 처리됐거나 취소된 로그인 흐름: {}", context.flow_id);
+            }
+        }
+        None => rejected("invalid_state"),
+    }
+}
+
+/// 진행 중인 로그인 취소. `flow_id` 가 없으면 진행 중인 모든 흐름을 취소한다. 취소된 흐름 수를 반환.
+#[tauri::command]
+pub fn cancel_oauth_flow(
+    registry: State<'_, OAuthFlowRegistry>,
+    flow_id: Option<String>,
+) -> Result<usize, String> {
+    let flows = registry
+        .flows
+        .lock()
+        .map_err(|_| "Failed to lock OAuth flows".to_string())?;
+    match flow_id {
+        Some(id) => {
+            let flow = flows
+                .get(id.trim())
+                .ok_or_else(|| "OAuth flow not found".to_string())?;
+            Ok(usize::from(flow.cancel()))
+        }
+        None => Ok(flows.values().filter(|flow| flow.cancel()).count()),
+    }
+}
+
+/// 흐름 상태 조회. `flow_id` 가 없으면 가장 최근에 시작한 흐름.
+#[tauri::command]
+pub fn get_oauth_flow_status(
+    registry: State<'_, OAuthFlowRegistry>,
+    flow_id: Option<String>,
+) -> Result<Option<OAuthFlowInfo>, String> {
+    let flows = registry
+        .flows
+        .lock()
+        .map_err(|_| "Failed to lock OAuth flows".to_string())?;
+    Ok(match flow_id {
+        Some(id) => flows.get(id.trim()).map(|flow| flow.info()),
+        None => flows
+            .values()
+            .map(|flow| flow.info())
+            .max_by_key(|info| (info.started_at_unix, info.flow_id.clone())),
+    })
+}
+
+/// 콜백 쿼리 스트링에서 OAuth 파라미터 파싱
+fn parse_oauth_callback(query: &str) -> Option<CallbackParams> {
+    let mut callback = CallbackParams::default();
 
     for param in query.split('&') {
         let mut parts = param.splitn(2, '=');
         let key = parts.next().unwrap_or("");
-        let value = parts
-            .next()
-            .map(|v| urlencoding::decode(v).unwrap_or_default().into_owned());
+        let value = parts.next().map(|v| {
+            urlencoding::decode(&v.replace('+', " "))
+                .unwrap_or_default()
+                .into_owned()
+        });
 
         match key {
-            "provider" => callback.provider = value,
-            "accessToken" => callback.access_token = value,
-            "refreshToken" => callback.refresh_token = value,
-            "memberId" => callback.member_id = value,
-            "nickname" => callback.nickname = value,
-            "email" => callback.email = value,
+            "code" => callback.code = value,
+            "state" => callback.state = value,
             "error" => callback.error = value,
+            "error_description" => callback.error_description = value,
             _ => {}
         }
     }
 
-    // 에러가 있거나 토큰이 있으면 유효한 콜백
-    if callback.error.is_some() || callback.access_token.is_some() {
+    // 에러가 있거나 인가 코드가 있으면 유효한 콜백
+    if callback.error.is_some() || callback.code.is_some() {
         Some(callback)
     } else {
         None
     }
 }
 
-/// 브라우저에 표시할 HTML 응답 생성
-fn create_html_response(callback: &OAuthCallback) -> String {
-    let (title, message, color) = if callback.error.is_some() {
-        (
-            "로그인 실패",
-            "로그인에 실패했습니다. 앱으로 돌아가 다시 시도해주세요.",
-            "#ef4444",
-        )
-    } else {
-        (
-            "로그인 성공",
-            "로그인이 완료되었습니다. 이 탭을 닫고 앱으로 돌아가세요.",
-            "#22c55e",
-        )
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::io::{Error, ErrorKind};
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="UTF-8">
-    <title>{}</title>
-    <style>
-        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
-        body {{
-            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
-            display: flex;
-            align-items: center;
-            justify-content: center;
-            min-height: 100vh;
-            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
-        }}
-        .card {{
-            background: white;
-            padding: 3rem;
-            border-radius: 1rem;
-            box-shadow: 0 25px 50px -12px rgba(0, 0, 0, 0.25);
-            text-align: center;
-            max-width: 400px;
-        }}
-        .icon {{
-            width: 80px;
-            height: 80px;
-            border-radius: 50%;
-            background: {};
-            display: flex;
-            align-items: center;
-            justify-content: center;
-            margin: 0 auto 1.5rem;
-        }}
-        .icon svg {{
-            width: 40px;
-            height: 40px;
-            color: white;
-        }}
-        h1 {{
-            color: #1f2937;
-            margin-bottom: 0.5rem;
-            font-size: 1.5rem;
-        }}
-        p {{
-            color: #6b7280;
-            line-height: 1.6;
-        }}
-    </style>
-</head>
-<body>
-    <div class="card">
-        <div class="icon">
-            <svg fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M5 13l4 4L19 7"></path>
-            </svg>
-        </div>
-        <h1>{}</h1>
-        <p>{}</p>
-    </div>
-    <script>
-        // 3초 후 자동으로 탭 닫기 시도
-        setTimeout(() => window.close(), 3000);
-    </script>
-</body>
-</html>"#,
-        title, color, title, message
-    );
+    /// 정해진 조각 단위로 돌려주는 소켓 대역. 조각이 다 떨어지면 EOF
+    struct ChunkedStream {
+        chunks: VecDeque<std::io::Result<Vec<u8>>>,
+    }
 
-    format!(
-        "HTTP/1.1 200 OK\r\n\
-         Content-Type: text/html; charset=utf-8\r\n\
-         Content-Length: {}\r\n\
-         Connection: close\r\n\
-         \r\n\
-         {}",
-        html.len(),
-        html
-    )
+    impl ChunkedStream {
+        fn new(chunks: Vec<std::io::Result<Vec<u8>>>) -> Self {
+            ChunkedStream {
+                chunks: chunks.into(),
+            }
+        }
+
+        fn of(parts: &[&str]) -> Self {
+            Self::new(parts.iter().map(|p| Ok(p.as_bytes().to_vec())).collect())
+        }
+    }
+
+    impl Read for ChunkedStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.chunks.pop_front() {
+                None => Ok(0),
+                Some(Err(e)) => Err(e),
+                Some(Ok(mut chunk)) => {
+                    let n = chunk.len().min(buf.len());
+                    buf[..n].copy_from_slice(&chunk[..n]);
+                    if n < chunk.len() {
+                        self.chunks.push_front(Ok(chunk.split_off(n)));
+                    }
+                    Ok(n)
+                }
+            }
+        }
+    }
+
+    fn request(head: &str) -> HttpRequest {
+        parse_request_head(head).unwrap()
+    }
+
+    #[test]
+    fn head_split_across_reads() {
+        // 구분자 `\r\n\r\n` 자체도 두 번의 read 에 걸쳐 있다
+        let mut stream = ChunkedStream::of(&[
+            "GET /callback?code=abc&st",
+            "ate=xyz HTTP/1.1\r\nHost: 127.0.0.1\r\n",
+            "User-Agent: test\r",
+            "\n\r\nignored body",
+        ]);
+        let request = read_request(&mut stream).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/callback");
+        assert_eq!(request.query, "code=abc&state=xyz");
+        assert_eq!(request.header("host"), Some("127.0.0.1"));
+        assert_eq!(request.header("user-agent"), Some("test"));
+    }
+
+    #[test]
+    fn oversized_head_is_rejected() {
+        let filler = format!("X-Filler: {}\r\n", "a".repeat(1024));
+        let mut parts = vec!["GET /callback HTTP/1.1\r\n".to_string()];
+        parts.extend(std::iter::repeat(filler).take(MAX_HEADER_BYTES / 1024 + 1));
+
+        // 끝을 보내지 않아도 한도를 넘으면 바로 거부
+        let mut stream =
+            ChunkedStream::new(parts.iter().map(|p| Ok(p.clone().into_bytes())).collect());
+        assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::TooLarge);
+
+        // 한 번에 들어온 요청도 헤더가 한도를 넘으면 거부
+        let head = format!("{}\r\n", parts.concat());
+        let mut stream = ChunkedStream::new(vec![Ok(head.into_bytes())]);
+        assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::TooLarge);
+    }
+
+    #[test]
+    fn head_just_under_the_limit_is_accepted() {
+        let prefix = "GET /callback HTTP/1.1\r\nX-Filler: ";
+        let filler = "a".repeat(MAX_HEADER_BYTES - prefix.len());
+        let head = format!("{}{}\r\n\r\n", prefix, filler);
+        let mut stream = ChunkedStream::new(vec![Ok(head.into_bytes())]);
+        assert!(read_request(&mut stream).is_ok());
+    }
+
+    #[test]
+    fn missing_terminator_is_malformed() {
+        let mut stream = ChunkedStream::of(&["GET /callback HTTP/1.1\r\nHost: x\r\n"]);
+        assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::Malformed);
+
+        let mut stream = ChunkedStream::of(&[]);
+        assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::Malformed);
+    }
+
+    #[test]
+    fn read_timeout_is_reported() {
+        for kind in [ErrorKind::WouldBlock, ErrorKind::TimedOut] {
+            let mut stream = ChunkedStream::new(vec![
+                Ok(b"GET /callback HTTP/1.1\r\n".to_vec()),
+                Err(Error::from(kind)),
+            ]);
+            assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::Timeout);
+        }
+
+        let mut stream = ChunkedStream::new(vec![Err(Error::from(ErrorKind::ConnectionReset))]);
+        assert_eq!(read_request(&mut stream).unwrap_err(), HttpError::Io);
+    }
+
+    #[test]
+    fn malformed_request_lines_are_rejected() {
+        for head in [
+            "GET /callback",
+            "GET  /callback HTTP/1.1",
+            "GET /callback HTTP/2",
+            "GET http://evil.example/callback HTTP/1.1",
+            "GET /callback HTTP/1.1 extra",
+            "GET /callback HTTP/1.1\r\nNo-Colon",
+            "GET /callback HTTP/1.1\r\nBad Name: x",
+            "GET /callback HTTP/1.1\r\nHost: x\r\n folded",
+        ] {
+            assert_eq!(
+                parse_request_head(head).unwrap_err(),
+                HttpError::Malformed,
+                "{}",
+                head
+            );
+        }
+    }
+
+    #[test]
+    fn only_get_is_allowed() {
+        for method in ["POST", "PUT", "DELETE", "HEAD", "OPTIONS", "get"] {
+            let request = request(&format!("{} /callback?code=a HTTP/1.1", method));
+            let (status, headers) = reject_non_callback(&request).unwrap();
+            assert_eq!(status, "405 Method Not Allowed", "{}", method);
+            assert_eq!(headers, &[("Allow", "GET")]);
+        }
+        assert!(reject_non_callback(&request("GET /callback?code=a HTTP/1.1")).is_none());
+    }
+
+    #[test]
+    fn callback_path_must_match_exactly() {
+        assert!(reject_non_callback(&request("GET /callback HTTP/1.1")).is_none());
+        assert!(reject_non_callback(&request("GET /callback?code=a#frag HTTP/1.1")).is_none());
+        for target in [
+            "/callbackx",
+            "/callback/",
+            "/callback/..",
+            "/callback/../callback",
+            "//callback",
+            "/Callback",
+            "/favicon.ico",
+        ] {
+            let request = request(&format!("GET {} HTTP/1.1", target));
+            assert_eq!(
+                reject_non_callback(&request).map(|(status, _)| status),
+                Some("404 Not Found"),
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn keep_alive_is_refused() {
+        let request = request("GET /callback?code=a HTTP/1.1\r\nConnection: keep-alive");
+        assert_eq!(request.header("connection"), Some("keep-alive"));
+
+        let mut response = Vec::new();
+        write_response(&mut response, "200 OK", &[], "ok");
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\nConnection: close\r\n"));
+        assert!(!response.to_ascii_lowercase().contains("keep-alive"));
+        assert!(response.ends_with("\r\n\r\nok"));
+    }
+
+    #[test]
+    fn redact_masks_codes_and_tokens_in_query() {
+        assert_eq!(
+            redact_url(
+                "http://127.0.0.1:4000/callback?code=abc&state=xyz&code_verifier=v&id_token=j&lang=ko"
+            ),
+            "http://127.0.0.1:4000/callback?code=[REDACTED]&state=[REDACTED]\
+             &code_verifier=[REDACTED]&id_token=[REDACTED]&lang=ko"
+        );
+        assert_eq!(
+            redact_url(
+                "deskcal://auth/callback?access_token=a&refreshToken=r&x_token_hint=h&page=2"
+            ),
+            "deskcal://auth/callback?access_token=[REDACTED]&refreshToken=[REDACTED]\
+             &x_token_hint=[REDACTED]&page=2"
+        );
+    }
+
+    #[test]
+    fn redact_ignores_parameter_name_case() {
+        assert_eq!(
+            redact_url("/callback?CODE=a&State=b&Access_Token=c&ID_TOKEN=d"),
+            "/callback?CODE=[REDACTED]&State=[REDACTED]&Access_Token=[REDACTED]&ID_TOKEN=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redact_masks_every_repeated_parameter() {
+        assert_eq!(
+            redact_url("/callback?code=a&code=b&token=c&token=d&next=/home"),
+            "/callback?code=[REDACTED]&code=[REDACTED]&token=[REDACTED]&token=[REDACTED]&next=/home"
+        );
+    }
+
+    #[test]
+    fn redact_masks_fragment_parameters() {
+        assert_eq!(
+            redact_url("deskcal://auth/callback#access_token=a&expires_in=3600&refresh_token=r"),
+            "deskcal://auth/callback#access_token=[REDACTED]&expires_in=3600&refresh_token=[REDACTED]"
+        );
+        assert_eq!(
+            redact_url("https://app.example/cb?state=s#id_token=t&code=c"),
+            "https://app.example/cb?state=[REDACTED]#id_token=[REDACTED]&code=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redact_keeps_request_line_suffix_and_plain_urls() {
+        assert_eq!(
+            redact_url("GET /callback?code=abc&state=xyz HTTP/1.1"),
+            "GET /callback?code=[REDACTED]&state=[REDACTED] HTTP/1.1"
+        );
+        assert_eq!(redact_url("deskcal://open"), "deskcal://open");
+        assert_eq!(
+            redact_url("/callback?flag&code="),
+            "/callback?flag&code=[REDACTED]"
+        );
+    }
 }
@@ -0,0 +1,251 @@
+/// Result page shown in the browser after the loopback OAuth callback.
+///
+/// Rendered from `templates/oauth_result.html` in the language and theme saved in
+/// `UserPreferences`. Every dynamic value is HTML-escaped, including the provider's
+/// `error_description`, since it comes straight from the callback query.
+use crate::account::UserPreferences;
+use crate::oauth::OAuthCallback;
+
+const TEMPLATE: &str = include_str!("../templates/oauth_result.html");
+/// "앱으로 돌아가기" 버튼이 여는 deep link
+pub const RETURN_TO_APP_URL: &str = "deskcal://open";
+const SUCCESS_ACCENT: &str = "#22c55e";
+const FAILURE_ACCENT: &str = "#ef4444";
+const CHECK_ICON: &str = "M5 13l4 4L19 7";
+const CROSS_ICON: &str = "M6 18L18 6M6 6l12 12";
+
+struct PageText {
+    success_title: &'static str,
+    success_message: &'static str,
+    failure_title: &'static str,
+    failure_message: &'static str,
+    return_label: &'static str,
+}
+
+const KO: PageText = PageText {
+    success_title: "로그인 성공",
+    success_message: "로그인이 완료되었습니다. 이 탭을 닫고 앱으로 돌아가세요.",
+    failure_title: "로그인 실패",
+    failure_message: "로그인에 실패했습니다. 앱으로 돌아가 다시 시도해주세요.",
+    return_label: "앱으로 돌아가기",
+};
+
+const EN: PageText = PageText {
+    success_title: "Signed in",
+    success_message: "You're signed in. You can close this tab and return to the app.",
+    failure_title: "Sign-in failed",
+    failure_message: "Sign-in didn't complete. Return to the app and try again.",
+    return_label: "Return to app",
+};
+
+/// 설정 언어에 맞는 문구 (지원하지 않는 언어는 영어)
+fn page_text(language: &str) -> (&'static str, &'static PageText) {
+    if language.trim().to_lowercase().starts_with("ko") {
+        ("ko", &KO)
+    } else {
+        ("en", &EN)
+    }
+}
+
+const LIGHT_CSS: &str = ":root { --background: linear-gradient(135deg, #667eea 0%, #764ba2 100%); --card: #ffffff; --title: #1f2937; --text: #6b7280; --detail: #f3f4f6; }";
+const DARK_CSS: &str = ":root { --background: #111827; --card: #1f2937; --title: #f9fafb; --text: #d1d5db; --detail: #374151; }";
+const PINK_CSS: &str = ":root { --background: linear-gradient(135deg, #fbcfe8 0%, #f472b6 100%); --card: #fff1f7; --title: #831843; --text: #9d174d; --detail: #fce7f3; }";
+
+/// 앱 테마(`light` / `dark` / `pink` / `system`)에 맞는 CSS 변수
+fn theme_css(theme: &str) -> String {
+    match theme.trim().to_lowercase().as_str() {
+        "dark" => DARK_CSS.to_string(),
+        "pink" => PINK_CSS.to_string(),
+        "light" => LIGHT_CSS.to_string(),
+        // system: 브라우저 설정을 따른다
+        _ => format!(
+            "{} @media (prefers-color-scheme: dark) {{ {} }}",
+            LIGHT_CSS, DARK_CSS
+        ),
+    }
+}
+
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `{{name}}` 자리표시자를 한 번에 치환. 치환된 값 안의 `{{...}}` 는 다시 해석하지 않는다.
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len() + 512);
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = &after[..end];
+                match values.iter().find(|(key, _)| *key == name) {
+                    Some((_, value)) => output.push_str(value),
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 브라우저에 표시할 로그인 결과 페이지
+pub fn render_result_page(callback: &OAuthCallback, preferences: &UserPreferences) -> String {
+    let (lang, text) = page_text(&preferences.language);
+    let failed = callback.error.is_some();
+    let (title, message, accent, icon) = if failed {
+        (
+            text.failure_title,
+            text.failure_message,
+            FAILURE_ACCENT,
+            CROSS_ICON,
+        )
+    } else {
+        (
+            text.success_title,
+            text.success_message,
+            SUCCESS_ACCENT,
+            CHECK_ICON,
+        )
+    };
+
+    // 공급자가 보낸 설명이 있으면 그것을, 없으면 에러 코드를 보여 준다
+    let detail = callback
+        .error_description
+        .as_deref()
+        .or(callback.error.as_deref())
+        .filter(|detail| failed && !detail.trim().is_empty())
+        .map(|detail| format!(r#"<p class="detail">{}</p>"#, escape_html(detail.trim())))
+        .unwrap_or_default();
+
+    let title = escape_html(title);
+    render(
+        TEMPLATE,
+        &[
+            ("lang", lang),
+            ("title", &title),
+            ("message", &escape_html(message)),
+            ("detail", &detail),
+            ("accent", accent),
+            ("icon_path", icon),
+            ("theme_css", &theme_css(&preferences.theme)),
+            ("return_url", RETURN_TO_APP_URL),
+            ("return_label", &escape_html(text.return_label)),
+            ("auto_close", if failed { "false" } else { "true" }),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preferences(language: &str) -> UserPreferences {
+        UserPreferences {
+            theme: "system".to_string(),
+            language: language.to_string(),
+            timezone: "Asia/Seoul".to_string(),
+            notifications_enabled: true,
+        }
+    }
+
+    fn callback(error: Option<&str>, error_description: Option<&str>) -> OAuthCallback {
+        OAuthCallback {
+            provider: Some("google".to_string()),
+            access_token: error.is_none().then(|| "token".to_string()),
+            refresh_token: None,
+            member_id: None,
+            nickname: None,
+            email: None,
+            expires_at_unix: None,
+            error: error.map(str::to_string),
+            error_description: error_description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn escapes_html_special_characters() {
+        assert_eq!(
+            escape_html(r#"<script>alert("x" & 'y')</script>"#),
+            "&lt;script&gt;alert(&quot;x&quot; &amp; &#39;y&#39;)&lt;/script&gt;"
+        );
+        assert_eq!(escape_html("로그인 실패"), "로그인 실패");
+        // 이미 이스케이프된 값도 그대로 두지 않고 다시 이스케이프
+        assert_eq!(escape_html("&lt;"), "&amp;lt;");
+    }
+
+    #[test]
+    fn provider_error_description_is_escaped() {
+        let page = render_result_page(
+            &callback(
+                Some("access_denied"),
+                Some(r#"<img src=x onerror="alert('x')">&{{title}}"#),
+            ),
+            &preferences("en"),
+        );
+        assert!(page.contains(
+            r#"<p class="detail">&lt;img src=x onerror=&quot;alert(&#39;x&#39;)&quot;&gt;&amp;{{title}}</p>"#
+        ));
+        assert!(!page.contains("<img"));
+    }
+
+    #[test]
+    fn error_code_is_shown_without_description() {
+        let page = render_result_page(&callback(Some("<denied>"), None), &preferences("en"));
+        assert!(page.contains(r#"<p class="detail">&lt;denied&gt;</p>"#));
+    }
+
+    #[test]
+    fn chooses_korean_or_english_text() {
+        let success = callback(None, None);
+
+        let page = render_result_page(&success, &preferences("ko"));
+        assert!(page.contains(r#"<html lang="ko">"#));
+        assert!(page.contains(KO.success_title));
+        assert!(page.contains(KO.return_label));
+
+        for language in ["en", "ja", ""] {
+            let page = render_result_page(&success, &preferences(language));
+            assert!(page.contains(r#"<html lang="en">"#), "{}", language);
+            assert!(page.contains(EN.success_title), "{}", language);
+        }
+        assert_eq!(page_text(" KO-kr ").0, "ko");
+
+        let page = render_result_page(&callback(Some("denied"), None), &preferences("ko"));
+        assert!(page.contains(KO.failure_title));
+        assert!(!page.contains(KO.success_title));
+    }
+
+    #[test]
+    fn only_the_success_page_closes_itself() {
+        let success = render_result_page(&callback(None, None), &preferences("en"));
+        assert!(success.contains("if (true)"));
+        assert!(!success.contains(r#"class="detail""#));
+
+        let failure = render_result_page(&callback(Some("denied"), None), &preferences("en"));
+        assert!(failure.contains("if (false)"));
+    }
+
+    #[test]
+    fn no_placeholders_are_left_unrendered() {
+        let page = render_result_page(&callback(None, None), &preferences("en"));
+        assert!(!page.contains("{{"));
+    }
+}
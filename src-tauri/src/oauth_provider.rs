@@ -0,0 +1,407 @@
+/// OAuth provider registry — built-in Google / Kakao definitions plus user-added generic
+/// OIDC providers resolved through `.well-known/openid-configuration`.
+///
+/// Built-in client ids are baked in at build time, like the API base URL. Every provider is
+/// treated as a public PKCE client, so no client secret ships with the app. Discovery
+/// documents are cached in memory.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::{account, api, storage};
+
+pub const OAUTH_PROVIDERS_KEY: &str = "oauth_providers";
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+const DISCOVERY_TTL_SECS: i64 = 60 * 60;
+
+struct BuiltinProvider {
+    id: &'static str,
+    display_name: &'static str,
+    client_id: Option<&'static str>,
+    authorize_endpoint: &'static str,
+    token_endpoint: &'static str,
+    userinfo_endpoint: &'static str,
+    issuer: &'static str,
+    /// ID token 의 `iss` 로 함께 허용하는 값
+    issuer_aliases: &'static [&'static str],
+    jwks_uri: &'static str,
+    scopes: &'static [&'static str],
+}
+
+const BUILTIN_PROVIDERS: [BuiltinProvider; 2] = [
+    BuiltinProvider {
+        id: "google",
+        display_name: "Google",
+        client_id: option_env!("VITE_GOOGLE_CLIENT_ID"),
+        authorize_endpoint: "https://accounts.google.com/o/oauth2/v2/auth",
+        token_endpoint: "https://oauth2.googleapis.com/token",
+        userinfo_endpoint: "https://www.googleapis.com/oauth2/v3/userinfo",
+        issuer: "https://accounts.google.com",
+        issuer_aliases: &["accounts.google.com"],
+        jwks_uri: "https://www.googleapis.com/oauth2/v3/certs",
+        scopes: &["openid", "email", "profile"],
+    },
+    BuiltinProvider {
+        id: "kakao",
+        display_name: "Kakao",
+        client_id: option_env!("VITE_KAKAO_CLIENT_ID"),
+        authorize_endpoint: "https://kauth.kakao.com/oauth/authorize",
+        token_endpoint: "https://kauth.kakao.com/oauth/token",
+        userinfo_endpoint: "https://kapi.kakao.com/v1/oidc/userinfo",
+        issuer: "https://kauth.kakao.com",
+        issuer_aliases: &[],
+        jwks_uri: "https://kauth.kakao.com/.well-known/jwks.json",
+        scopes: &["openid", "profile_nickname", "account_email"],
+    },
+];
+
+/// 사용자가 추가한 OIDC 공급자. 엔드포인트는 저장하지 않고 매번 discovery 로 찾는다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderConfig {
+    pub id: String,
+    pub display_name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub created_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthProviderKind {
+    Builtin,
+    Oidc,
+}
+
+/// 프론트엔드용 공급자 목록 항목 (비밀값 제외)
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthProviderInfo {
+    pub id: String,
+    pub display_name: String,
+    pub kind: OAuthProviderKind,
+    pub issuer: Option<String>,
+    pub scopes: Vec<String>,
+    /// client id 가 있어 바로 로그인할 수 있는지
+    pub configured: bool,
+}
+
+/// `.well-known/openid-configuration` 중 사용하는 항목
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    pub jwks_uri: String,
+    #[serde(default)]
+    pub code_challenge_methods_supported: Vec<String>,
+}
+
+/// 로그인에 필요한 값이 모두 채워진 공급자
+#[derive(Debug, Clone)]
+pub struct ResolvedProvider {
+    pub id: String,
+    pub client_id: String,
+    pub authorize_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: Option<String>,
+    pub issuer: Option<String>,
+    pub issuer_aliases: Vec<String>,
+    pub jwks_uri: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl ResolvedProvider {
+    /// `openid` 스코프를 요청하고 issuer / JWKS 를 알면 ID token 을 검증한다
+    pub fn is_oidc(&self) -> bool {
+        self.issuer.is_some()
+            && self.jwks_uri.is_some()
+            && self.scopes.iter().any(|s| s == "openid")
+    }
+
+    /// ID token `iss` 로 허용하는 값
+    pub fn accepted_issuers(&self) -> Vec<String> {
+        self.issuer
+            .iter()
+            .chain(self.issuer_aliases.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// PKCE(S256) + state (+ OIDC 면 nonce) 를 포함한 authorization code 요청 URL
+    pub fn authorize_url(
+        &self,
+        redirect_uri: &str,
+        state: &str,
+        code_challenge: &str,
+        nonce: &str,
+    ) -> String {
+        let separator = if self.authorize_endpoint.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let mut url = format!(
+            "{}{}response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&code_challenge={}&code_challenge_method=S256",
+            self.authorize_endpoint,
+            separator,
+            urlencoding::encode(&self.client_id),
+            urlencoding::encode(redirect_uri),
+            urlencoding::encode(&self.scopes.join(" ")),
+            state,
+            code_challenge,
+        );
+        if self.is_oidc() {
+            url.push_str("&nonce=");
+            url.push_str(nonce);
+        }
+        url
+    }
+}
+
+/// discovery 캐시 (issuer → 문서, 받은 시각). setup 에서 앱 상태로 등록한다.
+#[derive(Default)]
+pub struct OAuthProviderRegistry {
+    discovery: Mutex<HashMap<String, (OidcDiscovery, i64)>>,
+}
+
+impl OAuthProviderRegistry {
+    /// issuer 의 discovery 문서. 캐시가 만료됐으면 다시 받는다.
+    pub async fn discover(&self, issuer: &str) -> Result<OidcDiscovery, String> {
+        let issuer = normalize_issuer(issuer)?;
+        let now = account::now_unix();
+        {
+            let cache = self
+                .discovery
+                .lock()
+                .map_err(|_| "Failed to lock OIDC discovery cache".to_string())?;
+            if let Some((document, fetched_at)) = cache.get(&issuer) {
+                if now - fetched_at < DISCOVERY_TTL_SECS {
+                    return Ok(document.clone());
+                }
+            }
+        }
+
+        let url = format!("{}{}", issuer, DISCOVERY_PATH);
+        let document: OidcDiscovery = api::get_json_external(&url, None)
+            .await
+            .map_err(|e| format!("Failed to fetch OIDC discovery: {}", e))?;
+        validate_discovery(&issuer, &document)?;
+
+        self.discovery
+            .lock()
+            .map_err(|_| "Failed to lock OIDC discovery cache".to_string())?
+            .insert(issuer, (document.clone(), now));
+        Ok(document)
+    }
+
+    pub async fn resolve(
+        &self,
+        app: &tauri::AppHandle,
+        provider_id: &str,
+    ) -> Result<ResolvedProvider, String> {
+        let provider_id = provider_id.trim().to_lowercase();
+        if let Some(builtin) = BUILTIN_PROVIDERS.iter().find(|p| p.id == provider_id) {
+            let client_id = builtin
+                .client_id
+                .filter(|id| !id.trim().is_empty())
+                .ok_or_else(|| format!("OAuth provider {} is not configured", builtin.id))?;
+            return Ok(ResolvedProvider {
+                id: builtin.id.to_string(),
+                client_id: client_id.to_string(),
+                authorize_endpoint: builtin.authorize_endpoint.to_string(),
+                token_endpoint: builtin.token_endpoint.to_string(),
+                userinfo_endpoint: Some(builtin.userinfo_endpoint.to_string()),
+                issuer: Some(builtin.issuer.to_string()),
+                issuer_aliases: builtin
+                    .issuer_aliases
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                jwks_uri: Some(builtin.jwks_uri.to_string()),
+                scopes: builtin.scopes.iter().map(|s| s.to_string()).collect(),
+            });
+        }
+
+        let config = load_custom_providers(app)
+            .into_iter()
+            .find(|p| p.id == provider_id)
+            .ok_or_else(|| "OAuth provider not found".to_string())?;
+        let document = self.discover(&config.issuer).await?;
+        Ok(ResolvedProvider {
+            id: config.id,
+            client_id: config.client_id,
+            authorize_endpoint: document.authorization_endpoint,
+            token_endpoint: document.token_endpoint,
+            userinfo_endpoint: document.userinfo_endpoint,
+            issuer: Some(document.issuer),
+            issuer_aliases: Vec::new(),
+            jwks_uri: Some(document.jwks_uri),
+            scopes: config.scopes,
+        })
+    }
+}
+
+/// 토큰이 오가는 엔드포인트는 HTTPS 만 허용 (개발용 localhost 는 예외)
+pub fn ensure_https(label: &str, url: &str) -> Result<(), String> {
+    let secure = url.starts_with("https://")
+        || url.starts_with("http://localhost")
+        || url.starts_with("http://127.0.0.1");
+    if secure {
+        Ok(())
+    } else {
+        Err(format!("{} must use https", label))
+    }
+}
+
+fn normalize_issuer(issuer: &str) -> Result<String, String> {
+    let issuer = issuer.trim().trim_end_matches('/');
+    if issuer.is_empty() || issuer.contains(['?', '#']) {
+        return Err("Invalid OIDC issuer".to_string());
+    }
+    ensure_https("OIDC issuer", issuer)?;
+    Ok(issuer.to_string())
+}
+
+/// OIDC Discovery 1.0 §4.3: 문서의 issuer 는 요청한 issuer 와 같아야 한다
+fn validate_discovery(issuer: &str, document: &OidcDiscovery) -> Result<(), String> {
+    if document.issuer.trim_end_matches('/') != issuer {
+        return Err("OIDC discovery issuer does not match".to_string());
+    }
+    ensure_https("Authorization endpoint", &document.authorization_endpoint)?;
+    ensure_https("Token endpoint", &document.token_endpoint)?;
+    ensure_https("JWKS uri", &document.jwks_uri)?;
+    if let Some(userinfo) = document.userinfo_endpoint.as_deref() {
+        ensure_https("Userinfo endpoint", userinfo)?;
+    }
+    // 목록이 없으면 지원 여부를 알 수 없으므로 시도는 해 본다
+    let methods = &document.code_challenge_methods_supported;
+    if !methods.is_empty() && !methods.iter().any(|m| m == "S256") {
+        return Err("OIDC provider does not support PKCE (S256)".to_string());
+    }
+    Ok(())
+}
+
+fn load_custom_providers(app: &tauri::AppHandle) -> Vec<OidcProviderConfig> {
+    storage::read_json_or_log(app, OAUTH_PROVIDERS_KEY).unwrap_or_default()
+}
+
+fn custom_info(config: &OidcProviderConfig) -> OAuthProviderInfo {
+    OAuthProviderInfo {
+        id: config.id.clone(),
+        display_name: config.display_name.clone(),
+        kind: OAuthProviderKind::Oidc,
+        issuer: Some(config.issuer.clone()),
+        scopes: config.scopes.clone(),
+        configured: true,
+    }
+}
+
+fn validate_scopes(scopes: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let mut validated = vec!["openid".to_string()];
+    for scope in scopes.unwrap_or_else(|| vec!["email".to_string(), "profile".to_string()]) {
+        let scope = scope.trim().to_string();
+        if scope.is_empty() || scope.contains(char::is_whitespace) {
+            return Err("Invalid OAuth scope".to_string());
+        }
+        if !validated.contains(&scope) {
+            validated.push(scope);
+        }
+    }
+    Ok(validated)
+}
+
+/// 기본 제공 공급자 다음에 사용자가 추가한 OIDC 공급자
+#[tauri::command]
+pub fn list_oauth_providers(app: tauri::AppHandle) -> Vec<OAuthProviderInfo> {
+    let mut providers: Vec<OAuthProviderInfo> = BUILTIN_PROVIDERS
+        .iter()
+        .map(|p| OAuthProviderInfo {
+            id: p.id.to_string(),
+            display_name: p.display_name.to_string(),
+            kind: OAuthProviderKind::Builtin,
+            issuer: Some(p.issuer.to_string()),
+            scopes: p.scopes.iter().map(|s| s.to_string()).collect(),
+            configured: p.client_id.is_some_and(|id| !id.trim().is_empty()),
+        })
+        .collect();
+    providers.extend(load_custom_providers(&app).iter().map(custom_info));
+    providers
+}
+
+/// discovery 문서를 받아 검증한 뒤 OIDC 공급자를 추가
+#[tauri::command]
+pub async fn add_oidc_provider(
+    app: tauri::AppHandle,
+    registry: State<'_, OAuthProviderRegistry>,
+    id: String,
+    display_name: String,
+    issuer: String,
+    client_id: String,
+    scopes: Option<Vec<String>>,
+) -> Result<OAuthProviderInfo, String> {
+    let id = id.trim().to_lowercase();
+    if id.is_empty() || id.len() > 32 || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Provider id must be 1 to 32 letters or digits".to_string());
+    }
+    if BUILTIN_PROVIDERS.iter().any(|p| p.id == id) {
+        return Err("Provider id is reserved".to_string());
+    }
+    let display_name = display_name.trim().to_string();
+    if display_name.is_empty() || display_name.chars().count() > 50 {
+        return Err("Provider name must be 1 to 50 characters".to_string());
+    }
+    let client_id = client_id.trim().to_string();
+    if client_id.is_empty() {
+        return Err("Client id is required".to_string());
+    }
+    let issuer = normalize_issuer(&issuer)?;
+    registry.discover(&issuer).await?;
+
+    let config = OidcProviderConfig {
+        id,
+        display_name,
+        issuer,
+        client_id,
+        scopes: validate_scopes(scopes)?,
+        created_at_unix: account::now_unix(),
+    };
+    storage::update_json(
+        &app,
+        OAUTH_PROVIDERS_KEY,
+        Option::unwrap_or_default,
+        |providers: &mut Vec<OidcProviderConfig>| {
+            if providers.iter().any(|p| p.id == config.id) {
+                return Err("Provider id already exists".to_string());
+            }
+            providers.push(config.clone());
+            Ok(())
+        },
+    )?;
+    Ok(custom_info(&config))
+}
+
+#[tauri::command]
+pub fn remove_oidc_provider(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let id = id.trim().to_lowercase();
+    if BUILTIN_PROVIDERS.iter().any(|p| p.id == id) {
+        return Err("Built-in providers cannot be removed".to_string());
+    }
+    storage::update_json(
+        &app,
+        OAUTH_PROVIDERS_KEY,
+        Option::unwrap_or_default,
+        |providers: &mut Vec<OidcProviderConfig>| {
+            let before = providers.len();
+            providers.retain(|p| p.id != id);
+            if providers.len() == before {
+                return Err("OAuth provider not found".to_string());
+            }
+            Ok(())
+        },
+    )
+    .map(|_| ())
+}
@@ -1,14 +1,17 @@
 /// Window state persistence — save / restore position, size, opacity across launches.
 ///
-/// Stored as a small JSON file in the Tauri app-config directory.
+/// Stored as a JSON document in the shared app database. Move / resize events are
+/// coalesced so dragging the window does not hit the database on every event.
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::sync::Mutex;
 use tauri::Manager;
 
-const STATE_FILE: &str = "window_state.json";
+use crate::storage;
+
+pub const STATE_KEY: &str = "window_state";
 const MIN_WIDTH: f64 = 640.0;
 const MIN_HEIGHT: f64 = 480.0;
+const SAVE_DEBOUNCE_MS: u64 = 500;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowState {
@@ -26,51 +29,99 @@ fn default_width() -> f64 { 780.0 }
 fn default_height() -> f64 { 660.0 }
 fn default_opacity() -> f64 { 100.0 }
 
-fn state_path(app: &tauri::AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_config_dir()
-        .ok()
-        .map(|dir| dir.join(STATE_FILE))
+/// 아직 저장되지 않은 위치 / 크기 변경분
+struct PendingBounds {
+    position: Option<(f64, f64)>,
+    size: Option<(f64, f64)>,
+    flush_scheduled: bool,
 }
 
-/// 디스크에서 저장된 상태 읽기 (이전 형식도 호환)
+static PENDING_BOUNDS: Mutex<PendingBounds> = Mutex::new(PendingBounds {
+    position: None,
+    size: None,
+    flush_scheduled: false,
+});
+
+fn default_state() -> WindowState {
+    WindowState {
+        x: 100.0,
+        y: 100.0,
+        width: default_width(),
+        height: default_height(),
+        opacity: default_opacity(),
+    }
+}
+
+/// 저장된 상태 읽기 (이전 형식도 호환)
 pub fn load_state(app: &tauri::AppHandle) -> Option<WindowState> {
-    let path = state_path(app)?;
-    let data = fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&data).ok()
+    storage::read_json_or_log(app, STATE_KEY)
+}
+
+/// 현재 저장된 상태를 읽고 일부 필드만 업데이트하여 저장
+fn update_state(app: &tauri::AppHandle, f: impl FnOnce(&mut WindowState)) {
+    let result = storage::update_json(
+        app,
+        STATE_KEY,
+        |loaded| loaded.unwrap_or_else(default_state),
+        |state| {
+            f(state);
+            Ok(())
+        },
+    );
+    if let Err(e) = result {
+        log::warn!("state: failed to save window state: {}", e);
+    }
 }
 
-/// 현재 상태를 디스크에 저장
-pub fn save_state(app: &tauri::AppHandle, state: &WindowState) {
-    if let Some(path) = state_path(app) {
-        if let Some(parent) = path.parent() {
-            let _ = fs::create_dir_all(parent);
+/// 쌓인 변경분을 바로 저장. 창을 닫거나 앱을 끝낼 때도 호출해 디바운스 중인 변경분을 잃지 않는다
+pub fn flush_pending_bounds(app: &tauri::AppHandle) {
+    let (position, size) = {
+        let mut pending = PENDING_BOUNDS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        (pending.position.take(), pending.size.take())
+    };
+    if position.is_none() && size.is_none() {
+        return;
+    }
+    update_state(app, |s| {
+        if let Some((x, y)) = position {
+            s.x = x;
+            s.y = y;
         }
-        if let Ok(json) = serde_json::to_string_pretty(state) {
-            let _ = fs::write(&path, json);
+        if let Some((width, height)) = size {
+            s.width = width;
+            s.height = height;
         }
-    }
+    });
 }
 
-/// 현재 저장된 상태를 읽고 일부 필드만 업데이트하여 저장
-fn update_state(app: &tauri::AppHandle, f: impl FnOnce(&mut WindowState)) {
-    let mut state = load_state(app).unwrap_or(WindowState {
-        x: 100.0,
-        y: 100.0,
-        width: default_width(),
-        height: default_height(),
-        opacity: default_opacity(),
+/// 변경분을 기록하고, 예약된 저장이 없으면 잠시 뒤 한 번에 저장
+fn schedule_bounds_flush(app: &tauri::AppHandle, f: impl FnOnce(&mut PendingBounds)) {
+    let mut pending = PENDING_BOUNDS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut pending);
+    if pending.flush_scheduled {
+        return;
+    }
+    pending.flush_scheduled = true;
+    drop(pending);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(SAVE_DEBOUNCE_MS));
+        PENDING_BOUNDS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .flush_scheduled = false;
+        flush_pending_bounds(&app);
     });
-    f(&mut state);
-    save_state(app, &state);
 }
 
 /// 위치 저장
 pub fn save_position(app: &tauri::AppHandle, x: f64, y: f64) {
-    update_state(app, |s| {
-        s.x = x;
-        s.y = y;
-    });
+    schedule_bounds_flush(app, |pending| pending.position = Some((x, y)));
 }
 
 /// 크기 저장 (최소 크기 미만이면 무시)
@@ -78,10 +129,7 @@ pub fn save_size(app: &tauri::AppHandle, width: f64, height: f64) {
     if width < MIN_WIDTH || height < MIN_HEIGHT {
         return;
     }
-    update_state(app, |s| {
-        s.width = width;
-        s.height = height;
-    });
+    schedule_bounds_flush(app, |pending| pending.size = Some((width, height)));
 }
 
 /// 투명도 저장
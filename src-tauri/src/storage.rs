@@ -0,0 +1,462 @@
+/// Local persistence shared by every module — a single embedded SQLite database in the
+/// app-config directory.
+///
+/// Each module stores its state as a JSON document under a fixed key. All access goes
+/// through one connection guarded by a mutex, so read-modify-write cycles never interleave
+/// and `transaction` can update documents owned by several modules atomically.
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::account;
+
+const DATABASE_FILE: &str = "deskcal.db";
+const JSON_IMPORT_META_KEY: &str = "json_import_completed";
+
+/// 이전 버전에서 `<key>.json` 파일로 저장하던 문서들
+const LEGACY_JSON_DOCUMENTS: [&str; 6] = [
+    "account_settings",
+    "user_preferences",
+    "auth_session",
+    "workspace_state",
+    "alarm_state",
+    "window_state",
+];
+
+/// 스키마 마이그레이션. 인덱스 + 1 이 `PRAGMA user_version` 값이 된다. 기존 항목은 수정하지 말 것.
+const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE IF NOT EXISTS documents (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL,
+        updated_at_unix INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );
+"#];
+
+pub struct Database {
+    conn: Mutex<Connection>,
+}
+
+/// 하나의 SQLite 트랜잭션 안에서 문서를 읽고 쓰는 핸들
+pub struct Tx<'a> {
+    tx: rusqlite::Transaction<'a>,
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Database error: {}", e)
+}
+
+pub fn config_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+pub fn config_path(app: &tauri::AppHandle, filename: &str) -> Result<PathBuf, String> {
+    config_dir(app).map(|dir| dir.join(filename))
+}
+
+impl Tx<'_> {
+    pub fn get_raw(&self, key: &str) -> Result<Option<String>, String> {
+        self.tx
+            .query_row(
+                "SELECT value FROM documents WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// 문서가 없거나 현재 구조체로 해석할 수 없으면 None
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, String> {
+        Ok(self
+            .get_raw(key)?
+            .and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    pub fn put_raw(&self, key: &str, json: &str) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT INTO documents (key, value, updated_at_unix) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value,
+                                                updated_at_unix = excluded.updated_at_unix",
+                params![key, json, account::now_unix()],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<(), String> {
+        let json = serde_json::to_string(value)
+            .map_err(|e| format!("Failed to serialize json: {}", e))?;
+        self.put_raw(key, &json)
+    }
+
+    pub fn delete(&self, key: &str) -> Result<(), String> {
+        self.tx
+            .execute("DELETE FROM documents WHERE key = ?1", params![key])
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// `from` 으로 시작하는 키를 `to` 로 시작하도록 바꾸고 바뀐 문서 수를 반환
+    pub fn rename_prefix(&self, from: &str, to: &str) -> Result<usize, String> {
+        self.tx
+            .execute(
+                "UPDATE documents SET key = ?2 || substr(key, length(?1) + 1)
+                 WHERE substr(key, 1, length(?1)) = ?1",
+                params![from, to],
+            )
+            .map_err(db_error)
+    }
+
+    /// `prefix` 로 시작하는 키를 모두 삭제하고 삭제된 문서 수를 반환
+    pub fn delete_prefix(&self, prefix: &str) -> Result<usize, String> {
+        self.tx
+            .execute(
+                "DELETE FROM documents WHERE substr(key, 1, length(?1)) = ?1",
+                params![prefix],
+            )
+            .map_err(db_error)
+    }
+
+    fn meta(&self, key: &str) -> Result<Option<String>, String> {
+        self.tx
+            .query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(db_error)
+    }
+
+    fn set_meta(&self, key: &str, value: &str) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+}
+
+fn run_migrations(conn: &mut Connection) -> Result<(), String> {
+    let current: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+        .map_err(db_error)? as usize;
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let version = index + 1;
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute_batch(sql).map_err(db_error)?;
+        tx.pragma_update(None, "user_version", version as i64)
+            .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+        log::info!("storage: migrated schema to version {}", version);
+    }
+    Ok(())
+}
+
+/// 기존 JSON 파일을 한 번만 DB로 옮긴다. 옮긴 파일은 `.migrated` 로 이름을 바꿔 남겨 둔다.
+fn import_legacy_json(conn: &mut Connection, dir: &Path) -> Result<(), String> {
+    let tx = Tx {
+        tx: conn.transaction().map_err(db_error)?,
+    };
+    if tx.meta(JSON_IMPORT_META_KEY)?.is_some() {
+        return Ok(());
+    }
+
+    let mut imported: Vec<PathBuf> = Vec::new();
+    for key in LEGACY_JSON_DOCUMENTS {
+        let path = dir.join(format!("{}.json", key));
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(_) => continue,
+        };
+        if serde_json::from_str::<serde_json::Value>(&raw).is_err() {
+            log::warn!("storage: skipping unreadable legacy file {}", path.display());
+            continue;
+        }
+        if tx.get_raw(key)?.is_none() {
+            tx.put_raw(key, &raw)?;
+        }
+        imported.push(path);
+    }
+
+    tx.set_meta(JSON_IMPORT_META_KEY, &account::now_unix().to_string())?;
+    tx.tx.commit().map_err(db_error)?;
+
+    for path in imported {
+        let mut migrated = path.clone().into_os_string();
+        migrated.push(".migrated");
+        if let Err(e) = fs::rename(&path, &migrated) {
+            log::warn!("storage: failed to rename {}: {}", path.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// setup 에서 가장 먼저 호출 — DB 열기, 마이그레이션, JSON 가져오기 후 앱 상태로 등록
+pub fn init(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = config_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+    let mut conn = Connection::open(dir.join(DATABASE_FILE)).map_err(db_error)?;
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(db_error)?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .map_err(db_error)?;
+
+    run_migrations(&mut conn)?;
+    import_legacy_json(&mut conn, &dir)?;
+
+    app.manage(Database {
+        conn: Mutex::new(conn),
+    });
+    Ok(())
+}
+
+impl Database {
+    /// 테스트용 메모리 DB
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, String> {
+        let mut conn = Connection::open_in_memory().map_err(db_error)?;
+        run_migrations(&mut conn)?;
+        Ok(Database {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn transaction<R>(&self, f: impl FnOnce(&Tx) -> Result<R, String>) -> Result<R, String> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| "Failed to lock database".to_string())?;
+
+        let tx = Tx {
+            tx: conn.transaction().map_err(db_error)?,
+        };
+        let result = f(&tx)?;
+        tx.tx.commit().map_err(db_error)?;
+        Ok(result)
+    }
+}
+
+/// 여러 모듈의 문서를 한 트랜잭션으로 갱신. `f`가 에러를 반환하면 모두 롤백된다.
+pub fn transaction<R>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&Tx) -> Result<R, String>,
+) -> Result<R, String> {
+    app.try_state::<Database>()
+        .ok_or_else(|| "Database is not initialized".to_string())?
+        .transaction(f)
+}
+
+/// 문서가 없으면 `Ok(None)`. DB 오류는 "없음" 과 구분해 에러로 돌려준다
+pub fn read_json<T: DeserializeOwned>(app: &tauri::AppHandle, key: &str) -> Result<Option<T>, String> {
+    transaction(app, |tx| tx.get(key))
+}
+
+/// 읽지 못해도 기본값으로 계속해도 되는 조회용 — DB 오류는 로그로 남기고 None.
+/// 읽은 값을 고쳐 다시 저장할 때는 `read_json` 이나 `update_json` 을 쓸 것
+pub fn read_json_or_log<T: DeserializeOwned>(app: &tauri::AppHandle, key: &str) -> Option<T> {
+    read_json(app, key).unwrap_or_else(|e| {
+        log::warn!("storage: failed to read {}: {}", key, e);
+        None
+    })
+}
+
+pub fn write_json<T: Serialize>(app: &tauri::AppHandle, key: &str, value: &T) -> Result<(), String> {
+    transaction(app, |tx| tx.put(key, value))
+}
+
+/// 트랜잭션 안에서 읽기 → 수정 → 쓰기. `f`가 에러를 반환하면 문서는 바뀌지 않는다.
+pub fn update_json<T, R>(
+    app: &tauri::AppHandle,
+    key: &str,
+    load: impl FnOnce(Option<T>) -> T,
+    f: impl FnOnce(&mut T) -> Result<R, String>,
+) -> Result<(T, R), String>
+where
+    T: Serialize + DeserializeOwned,
+{
+    transaction(app, |tx| {
+        let mut value = load(tx.get(key)?);
+        let result = f(&mut value)?;
+        tx.put(key, &value)?;
+        Ok((value, result))
+    })
+}
+
+pub fn remove(app: &tauri::AppHandle, key: &str) -> Result<(), String> {
+    transaction(app, |tx| tx.delete(key))
+}
+
+/// 프로필 이미지 등 DB 밖에 두는 파일용: 임시 파일에 쓰고 fsync 후 rename
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+    let result = (|| {
+        let mut file = File::create(&tmp).map_err(|e| format!("Failed to write file: {}", e))?;
+        file.write_all(data)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to flush file: {}", e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to replace file: {}", e))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_version(conn: &Connection) -> i64 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn migrated_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn
+    }
+
+    fn document(conn: &mut Connection, key: &str) -> Option<String> {
+        let tx = Tx {
+            tx: conn.transaction().unwrap(),
+        };
+        tx.get_raw(key).unwrap()
+    }
+
+    fn legacy_dir() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("deskcal-storage-{}", uuid::Uuid::now_v7().simple()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrates_from_version_zero() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(user_version(&conn), 0);
+
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len() as i64);
+        let tables: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('documents', 'meta')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tables, 2);
+
+        // 이미 최신이면 아무것도 다시 실행하지 않는다
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn legacy_json_is_imported_once() {
+        let mut conn = migrated_connection();
+        let dir = legacy_dir();
+        fs::write(dir.join("account_settings.json"), r#"{"nickname":"old"}"#).unwrap();
+        fs::write(dir.join("window_state.json"), r#"{"x":1,"y":2}"#).unwrap();
+
+        import_legacy_json(&mut conn, &dir).unwrap();
+        assert_eq!(
+            document(&mut conn, "account_settings").as_deref(),
+            Some(r#"{"nickname":"old"}"#)
+        );
+        assert!(document(&mut conn, "window_state").is_some());
+        assert!(!dir.join("account_settings.json").exists());
+        assert!(dir.join("account_settings.json.migrated").exists());
+        assert!(dir.join("window_state.json.migrated").exists());
+
+        // 두 번째 실행은 새로 생긴 파일이 있어도 가져오지 않고 DB 값도 건드리지 않는다
+        fs::write(dir.join("account_settings.json"), r#"{"nickname":"new"}"#).unwrap();
+        import_legacy_json(&mut conn, &dir).unwrap();
+        assert_eq!(
+            document(&mut conn, "account_settings").as_deref(),
+            Some(r#"{"nickname":"old"}"#)
+        );
+        assert!(dir.join("account_settings.json").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn legacy_import_keeps_existing_documents() {
+        let mut conn = migrated_connection();
+        {
+            let tx = Tx {
+                tx: conn.transaction().unwrap(),
+            };
+            tx.put_raw("user_preferences", r#"{"language":"en"}"#)
+                .unwrap();
+            tx.tx.commit().unwrap();
+        }
+        let dir = legacy_dir();
+        fs::write(dir.join("user_preferences.json"), r#"{"language":"ko"}"#).unwrap();
+
+        import_legacy_json(&mut conn, &dir).unwrap();
+        assert_eq!(
+            document(&mut conn, "user_preferences").as_deref(),
+            Some(r#"{"language":"en"}"#)
+        );
+        assert!(dir.join("user_preferences.json.migrated").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn corrupt_legacy_json_is_skipped_and_left_in_place() {
+        let mut conn = migrated_connection();
+        let dir = legacy_dir();
+        fs::write(dir.join("workspace_state.json"), "{not json").unwrap();
+        fs::write(dir.join("alarm_state.json"), "").unwrap();
+        fs::write(dir.join("auth_session.json"), r#"{"access_token":"t"}"#).unwrap();
+
+        import_legacy_json(&mut conn, &dir).unwrap();
+        assert!(document(&mut conn, "workspace_state").is_none());
+        assert!(document(&mut conn, "alarm_state").is_none());
+        assert!(document(&mut conn, "auth_session").is_some());
+        assert!(dir.join("workspace_state.json").exists());
+        assert!(!dir.join("workspace_state.json.migrated").exists());
+        assert!(dir.join("alarm_state.json").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn corrupt_document_reads_as_missing() {
+        let db = Database::open_in_memory().unwrap();
+        db.transaction(|tx| {
+            tx.put_raw("window_state", "{broken")?;
+            assert!(tx.get::<serde_json::Value>("window_state")?.is_none());
+            assert_eq!(tx.get_raw("window_state")?.as_deref(), Some("{broken"));
+            Ok(())
+        })
+        .unwrap();
+    }
+}
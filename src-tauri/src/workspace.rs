@@ -1,10 +1,41 @@
+use chrono::{Local, Offset, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::Manager;
+use tauri::Emitter;
+use uuid::Uuid;
 
-const WORKSPACE_STATE_FILE: &str = "workspace_state.json";
+use crate::entitlement::{self, Entitlements, UpgradeRequired};
+use crate::{account, alarm, storage};
+
+pub const WORKSPACE_STATE_KEY: &str = "workspace_state";
+/// 로그아웃 / 가져오기로 상태 문서가 지워져도 revision 은 계속 증가하도록 따로 저장
+const WORKSPACE_REVISION_KEY: &str = "workspace_revision";
+const WORKSPACE_CHANGED_EVENT: &str = "workspace://changed";
+/// 워크스페이스별 캐시 문서 키 접두사 (`workspace_cache:<id>:<name>`). 영구 삭제 시 함께 지워진다.
+const WORKSPACE_CACHE_PREFIX: &str = "workspace_cache";
+/// 워크스페이스별 파일을 두는 설정 디렉터리 하위 폴더
+const WORKSPACE_FILES_DIR: &str = "workspaces";
+
+/// 워크스페이스를 가리키는 식별자. 로컬 id (`ws_...`) 또는 서버 숫자 id 중 하나이며,
+/// 알람처럼 프론트엔드가 서버 id 로 넘겨주는 데이터도 같은 타입으로 다룬다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum WorkspaceRef {
+    Server(i64),
+    Local(String),
+}
+
+impl fmt::Display for WorkspaceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceRef::Server(id) => write!(f, "{}", id),
+            WorkspaceRef::Local(id) => f.write_str(id),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -13,6 +44,59 @@ pub enum WorkspaceType {
     Team,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceSyncState {
+    /// 서버에 아직 없는 워크스페이스 (오프라인 생성 등)
+    #[default]
+    LocalOnly,
+    Synced,
+    /// 로컬과 서버가 서로 다르게 바뀌어 사용자 확인이 필요
+    Conflict,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarView {
+    #[default]
+    Month,
+    Week,
+    Day,
+    List,
+}
+
+/// 워크스페이스별 표시 설정
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WorkspaceSettings {
+    /// `#RRGGBB`
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 이모지 또는 아이콘 키
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub default_view: CalendarView,
+    /// 새 할 일의 기본 색상 (`#RRGGBB`)
+    #[serde(default)]
+    pub default_task_color: Option<String>,
+    /// 새 일정의 기본 알림 (시작 몇 분 전)
+    #[serde(default)]
+    pub default_reminder_minutes: Option<u32>,
+    /// IANA 타임존 (예: `Asia/Seoul`). 없으면 시스템 타임존을 따른다.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// 워크스페이스 타임존과 현재 기기 타임존의 UTC 오프셋 (분)
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceTimeZone {
+    pub workspace_id: String,
+    pub timezone: Option<String>,
+    pub utc_offset_minutes: i32,
+    pub local_utc_offset_minutes: i32,
+    pub differs_from_local: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
     pub id: String,
@@ -24,6 +108,113 @@ pub struct Workspace {
     pub team_id: Option<i64>,
     pub created_at_unix: i64,
     pub updated_at_unix: i64,
+    /// 서버 `/api/me/workspaces` 의 workspace_id
+    #[serde(default)]
+    pub server_id: Option<i64>,
+    /// 마지막 동기화 시점의 서버 이름 (3-way 비교 기준)
+    #[serde(default)]
+    pub synced_name: Option<String>,
+    #[serde(default)]
+    pub sync_state: WorkspaceSyncState,
+    /// 보관 처리된 시각. 보관된 워크스페이스는 기본 목록에서 숨겨진다.
+    #[serde(default)]
+    pub archived_at_unix: Option<i64>,
+    /// 휴지통으로 이동한 시각. 보관 기간이 지나면 영구 삭제된다.
+    #[serde(default)]
+    pub deleted_at_unix: Option<i64>,
+    /// 사용자가 정한 표시 순서 (작을수록 위)
+    #[serde(default)]
+    pub sort_order: i64,
+    /// 즐겨찾기 — 목록 맨 위에 표시
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub settings: WorkspaceSettings,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRole {
+    Owner,
+    Admin,
+    Member,
+    Viewer,
+}
+
+impl TeamRole {
+    /// 이름 변경, 멤버 초대
+    pub fn can_manage(self) -> bool {
+        matches!(self, TeamRole::Owner | TeamRole::Admin)
+    }
+
+    /// 휴지통 이동, 영구 삭제
+    pub fn can_delete(self) -> bool {
+        matches!(self, TeamRole::Owner)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub member_id: i64,
+    pub role: TeamRole,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub joined_at: Option<String>,
+}
+
+/// 팀 API 에서 받아 둔 팀 멤버 목록과 현재 사용자의 역할
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRoster {
+    pub team_id: i64,
+    pub members: Vec<TeamMember>,
+    #[serde(default)]
+    pub my_role: Option<TeamRole>,
+    pub fetched_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TeamAction {
+    Rename,
+    Delete,
+}
+
+/// 사이드바에서 워크스페이스를 묶는 폴더
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceGroup {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceConflictKind {
+    /// 로컬과 서버 양쪽에서 이름이 다르게 바뀜
+    NameChanged,
+    /// 로컬에서 수정했지만 서버에서는 삭제됨
+    DeletedOnServer,
+    /// 오프라인에서 만든 워크스페이스와 같은 이름이 서버에 이미 있음
+    DuplicateName,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceConflict {
+    pub workspace_id: String,
+    pub kind: WorkspaceConflictKind,
+    pub local_name: String,
+    #[serde(default)]
+    pub server_id: Option<i64>,
+    #[serde(default)]
+    pub server_name: Option<String>,
+    pub detected_at_unix: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +223,52 @@ pub struct WorkspaceState {
     pub current_workspace_id: Option<String>,
     #[serde(default)]
     pub workspaces: Vec<Workspace>,
+    #[serde(default)]
+    pub conflicts: Vec<WorkspaceConflict>,
+    /// 로컬에서 삭제되어 다음 동기화 때 서버에서도 지울 워크스페이스
+    #[serde(default)]
+    pub pending_server_deletions: Vec<i64>,
+    #[serde(default)]
+    pub last_synced_at_unix: Option<i64>,
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    #[serde(default)]
+    pub groups: Vec<WorkspaceGroup>,
+    /// 앱 시작 시 열 워크스페이스. 없으면 마지막으로 사용한 워크스페이스를 유지한다.
+    #[serde(default)]
+    pub launch_workspace_id: Option<String>,
+    #[serde(default)]
+    pub team_rosters: Vec<TeamRoster>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceChangeReason {
+    Updated,
+    /// 상태 전체가 교체됨 — 차이 목록 대신 전체 상태를 다시 읽을 것
+    Reloaded,
+}
+
+/// `workspace://changed` 페이로드. revision 이 건너뛰면 놓친 이벤트가 있으므로 스냅샷을 다시 읽는다.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceChangedEvent {
+    pub revision: u64,
+    pub reason: WorkspaceChangeReason,
+    pub current_workspace_id: Option<String>,
+    /// `current_changed` 일 때 이전 워크스페이스
+    pub previous_workspace_id: Option<String>,
+    pub current_changed: bool,
+    pub added: Vec<Workspace>,
+    pub updated: Vec<Workspace>,
+    pub removed: Vec<String>,
+    /// 그룹, 충돌, 팀 멤버 등 워크스페이스 목록 밖의 상태가 바뀜
+    pub other_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSnapshot {
+    pub revision: u64,
+    pub state: WorkspaceState,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -39,6 +276,7 @@ pub struct TeamWorkspaceCreationPath {
     pub status: String,
     pub route: String,
     pub message: String,
+    pub upgrade: Option<UpgradeRequired>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -47,47 +285,36 @@ pub struct TeamWorkspaceCreationResult {
     pub message: String,
     pub route: String,
     pub workspace: Option<Workspace>,
+    pub upgrade: Option<UpgradeRequired>,
 }
 
-fn now_unix() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
+fn default_trash_retention_days() -> u32 {
+    30
 }
 
-fn new_team_id() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or_else(|_| now_unix())
+/// UUIDv7 기반 id — 시간순 정렬이 되고 같은 밀리초나 다른 기기에서 만들어도 겹치지 않는다
+pub fn new_workspace_id() -> String {
+    format!("ws_{}", Uuid::now_v7().simple())
 }
 
-fn new_workspace_id() -> String {
-    let ts = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0);
-    format!("ws_{}", ts)
+/// 이전 버전의 `ws_<밀리초>` 형식 id
+fn is_legacy_workspace_id(id: &str) -> bool {
+    id.strip_prefix("ws_")
+        .map(|ts| !ts.is_empty() && ts.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
 }
 
-fn normalize_plan(plan_tier: &str) -> String {
-    let normalized = plan_tier.trim().to_lowercase();
-    if normalized.is_empty() {
-        "free".to_string()
-    } else {
-        normalized
+fn ensure_unique_ids(state: &WorkspaceState) -> Result<(), String> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    for ws in &state.workspaces {
+        if !seen.insert(ws.id.as_str()) {
+            return Err(format!("Duplicate workspace id: {}", ws.id));
+        }
     }
+    Ok(())
 }
 
-fn is_paid_plan(plan_tier: &str) -> bool {
-    matches!(
-        normalize_plan(plan_tier).as_str(),
-        "pro" | "plus" | "team" | "business" | "enterprise" | "paid" | "premium"
-    )
-}
-
-fn validate_workspace_name(name: &str) -> Result<String, String> {
+pub fn validate_workspace_name(name: &str) -> Result<String, String> {
     let trimmed = name.trim();
     let len = trimmed.chars().count();
     if len == 0 {
@@ -99,92 +326,487 @@ fn validate_workspace_name(name: &str) -> Result<String, String> {
     Ok(trimmed.to_string())
 }
 
-fn workspace_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(WORKSPACE_STATE_FILE))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
-}
-
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
-}
-
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+pub fn empty_state() -> WorkspaceState {
+    WorkspaceState {
+        current_workspace_id: None,
+        workspaces: Vec::new(),
+        conflicts: Vec::new(),
+        pending_server_deletions: Vec::new(),
+        last_synced_at_unix: None,
+        trash_retention_days: default_trash_retention_days(),
+        groups: Vec::new(),
+        launch_workspace_id: None,
+        team_rosters: Vec::new(),
     }
-    let json = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
 }
 
-fn default_personal_workspace() -> Workspace {
-    let now = now_unix();
+pub fn new_workspace(name: String, workspace_type: WorkspaceType) -> Workspace {
+    let now = account::now_unix();
     Workspace {
         id: new_workspace_id(),
-        name: "내 워크스페이스".to_string(),
-        workspace_type: WorkspaceType::Personal,
+        name,
+        workspace_type,
         plan_tier: None,
         team_id: None,
         created_at_unix: now,
         updated_at_unix: now,
+        server_id: None,
+        synced_name: None,
+        sync_state: WorkspaceSyncState::LocalOnly,
+        archived_at_unix: None,
+        deleted_at_unix: None,
+        sort_order: 0,
+        pinned: false,
+        group_id: None,
+        settings: WorkspaceSettings::default(),
+    }
+}
+
+/// 목록 맨 뒤 순서를 부여해 추가하고, 추가된 사본을 반환
+pub fn push_workspace(state: &mut WorkspaceState, mut workspace: Workspace) -> Workspace {
+    workspace.sort_order = state
+        .workspaces
+        .iter()
+        .map(|ws| ws.sort_order + 1)
+        .max()
+        .unwrap_or(0);
+    state.workspaces.push(workspace.clone());
+    workspace
+}
+
+/// 표시 순서: 고정 → 사용자 순서 → 생성 시각
+fn sort_for_display(workspaces: &mut [Workspace]) {
+    workspaces.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(a.sort_order.cmp(&b.sort_order))
+            .then(a.created_at_unix.cmp(&b.created_at_unix))
+    });
+}
+
+/// 보관되지도, 휴지통에 있지도 않은 워크스페이스
+pub fn is_active(ws: &Workspace) -> bool {
+    ws.archived_at_unix.is_none() && ws.deleted_at_unix.is_none()
+}
+
+/// 워크스페이스를 완전히 제거. 서버에 있는 것은 다음 동기화 때 서버에서도 삭제한다.
+fn purge_from_state(state: &mut WorkspaceState, workspace_id: &str) {
+    if let Some(server_id) = state
+        .workspaces
+        .iter()
+        .find(|ws| ws.id == workspace_id)
+        .and_then(|ws| ws.server_id)
+    {
+        state.pending_server_deletions.push(server_id);
+    }
+    state.conflicts.retain(|c| c.workspace_id != workspace_id);
+    state.workspaces.retain(|ws| ws.id != workspace_id);
+}
+
+/// 보관 기간이 지난 휴지통 항목 영구 삭제
+fn purge_expired_trash(state: &mut WorkspaceState, now: i64) {
+    let retention_secs = state.trash_retention_days as i64 * 24 * 60 * 60;
+    let expired: Vec<String> = state
+        .workspaces
+        .iter()
+        .filter(|ws| {
+            ws.deleted_at_unix
+                .map(|deleted_at| now - deleted_at >= retention_secs)
+                .unwrap_or(false)
+        })
+        .map(|ws| ws.id.clone())
+        .collect();
+    for id in expired {
+        purge_from_state(state, &id);
+    }
+}
+
+/// 마지막 남은 활성 개인 워크스페이스는 보관 / 삭제할 수 없다
+fn ensure_other_active_personal(state: &WorkspaceState, workspace_id: &str) -> Result<(), String> {
+    let target_is_personal = state
+        .workspaces
+        .iter()
+        .any(|ws| ws.id == workspace_id && ws.workspace_type == WorkspaceType::Personal);
+    if !target_is_personal {
+        return Ok(());
+    }
+    let others = state
+        .workspaces
+        .iter()
+        .filter(|ws| ws.id != workspace_id && ws.workspace_type == WorkspaceType::Personal)
+        .filter(|ws| is_active(ws))
+        .count();
+    if others == 0 {
+        return Err("At least one personal workspace is required".to_string());
     }
+    Ok(())
+}
+
+fn default_personal_workspace() -> Workspace {
+    new_workspace("내 워크스페이스".to_string(), WorkspaceType::Personal)
 }
 
 fn ensure_valid_state(mut state: WorkspaceState) -> WorkspaceState {
-    if state.workspaces.is_empty() {
-        let ws = default_personal_workspace();
+    purge_expired_trash(&mut state, account::now_unix());
+
+    if !state.workspaces.iter().any(is_active) {
+        let ws = push_workspace(&mut state, default_personal_workspace());
         state.current_workspace_id = Some(ws.id.clone());
-        state.workspaces.push(ws);
         return state;
     }
 
+    let group_ids: HashSet<String> = state.groups.iter().map(|g| g.id.clone()).collect();
+    for ws in state.workspaces.iter_mut() {
+        if ws.group_id.as_ref().is_some_and(|id| !group_ids.contains(id)) {
+            ws.group_id = None;
+        }
+    }
+    let team_ids: HashSet<i64> = state.workspaces.iter().filter_map(|ws| ws.team_id).collect();
+    state.team_rosters.retain(|r| team_ids.contains(&r.team_id));
+    let launch_exists = state
+        .launch_workspace_id
+        .as_ref()
+        .map(|id| state.workspaces.iter().any(|ws| &ws.id == id && is_active(ws)))
+        .unwrap_or(false);
+    if !launch_exists {
+        state.launch_workspace_id = None;
+    }
+
     let current_exists = state
         .current_workspace_id
         .as_ref()
-        .map(|id| state.workspaces.iter().any(|ws| &ws.id == id))
+        .map(|id| state.workspaces.iter().any(|ws| &ws.id == id && is_active(ws)))
         .unwrap_or(false);
 
     if !current_exists {
-        state.current_workspace_id = state.workspaces.first().map(|ws| ws.id.clone());
+        let mut active: Vec<Workspace> =
+            state.workspaces.iter().filter(|ws| is_active(ws)).cloned().collect();
+        sort_for_display(&mut active);
+        state.current_workspace_id = state
+            .launch_workspace_id
+            .clone()
+            .or_else(|| active.first().map(|ws| ws.id.clone()));
     }
 
     state
 }
 
-fn load_state(app: &tauri::AppHandle) -> WorkspaceState {
-    let path = match workspace_state_path(app) {
-        Ok(path) => path,
-        Err(_) => {
-            return ensure_valid_state(WorkspaceState {
-                current_workspace_id: None,
-                workspaces: Vec::new(),
-            });
-        }
-    };
-
-    let state = read_json::<WorkspaceState>(&path).unwrap_or(WorkspaceState {
-        current_workspace_id: None,
-        workspaces: Vec::new(),
-    });
+pub fn load_state(app: &tauri::AppHandle) -> WorkspaceState {
+    let state =
+        storage::read_json_or_log::<WorkspaceState>(app, WORKSPACE_STATE_KEY).unwrap_or_else(empty_state);
     ensure_valid_state(state)
 }
 
-fn save_state(app: &tauri::AppHandle, state: &WorkspaceState) -> Result<(), String> {
-    let path = workspace_state_path(app)?;
-    write_json(&path, state)
-}
-
-fn update_state(
+pub fn update_state(
     app: &tauri::AppHandle,
     f: impl FnOnce(&mut WorkspaceState) -> Result<(), String>,
 ) -> Result<WorkspaceState, String> {
-    let mut state = load_state(app);
+    let (state, event, cascade) = storage::transaction(app, |tx| update_state_in(tx, f))?;
+
+    apply_cascade(app, &cascade);
+    if let Some(event) = event {
+        emit_changed(app, event);
+    }
+    Ok(state)
+}
+
+/// 트랜잭션 안에서 상태를 바꾸고 저장. 영구 삭제된 워크스페이스의 캐시 문서도 같은 트랜잭션에서 지운다.
+fn update_state_in(
+    tx: &storage::Tx,
+    f: impl FnOnce(&mut WorkspaceState) -> Result<(), String>,
+) -> Result<(WorkspaceState, Option<WorkspaceChangedEvent>, Cascade), String> {
+    let stored = tx
+        .get::<WorkspaceState>(WORKSPACE_STATE_KEY)?
+        .unwrap_or_else(empty_state);
+    let previous = ensure_valid_state(stored.clone());
+    let mut state = previous.clone();
     f(&mut state)?;
+    ensure_unique_ids(&state)?;
     let state = ensure_valid_state(state);
-    save_state(app, &state)?;
-    Ok(state)
+    tx.put(WORKSPACE_STATE_KEY, &state)?;
+
+    // 보관 기간이 지나 읽을 때 이미 빠진 휴지통 항목도 정리되도록 저장된 원본과 비교
+    let cascade = collect_cascade(&stored, &state);
+    for id in &cascade.purged_ids {
+        tx.delete_prefix(&format!("{}:{}:", WORKSPACE_CACHE_PREFIX, id))?;
+    }
+
+    let event = match diff_states(&previous, &state) {
+        Some(mut event) => {
+            event.revision = tx.get::<u64>(WORKSPACE_REVISION_KEY)?.unwrap_or(0) + 1;
+            tx.put(WORKSPACE_REVISION_KEY, &event.revision)?;
+            Some(event)
+        }
+        None => None,
+    };
+    Ok((state, event, cascade))
+}
+
+/// 삭제에 따라 함께 정리할 대상
+#[derive(Default)]
+struct Cascade {
+    /// 알람을 지울 워크스페이스 (휴지통 이동 + 영구 삭제)
+    alarm_refs: Vec<WorkspaceRef>,
+    /// 캐시 문서와 파일까지 지울 워크스페이스 (영구 삭제)
+    purged_ids: Vec<String>,
+}
+
+fn collect_cascade(previous: &WorkspaceState, next: &WorkspaceState) -> Cascade {
+    let mut cascade = Cascade::default();
+    for old in &previous.workspaces {
+        let current = next.workspaces.iter().find(|ws| ws.id == old.id);
+        let purged = current.is_none();
+        let trashed = current.is_some_and(|ws| ws.deleted_at_unix.is_some() && old.deleted_at_unix.is_none());
+        if !purged && !trashed {
+            continue;
+        }
+
+        cascade.alarm_refs.push(WorkspaceRef::Local(old.id.clone()));
+        // 서버 id 는 같은 서버 워크스페이스에 연결된 다른 활성 워크스페이스가 없을 때만 정리
+        if let Some(server_id) = old.server_id {
+            let still_linked = next
+                .workspaces
+                .iter()
+                .any(|ws| ws.id != old.id && ws.server_id == Some(server_id) && is_active(ws));
+            if !still_linked {
+                cascade.alarm_refs.push(WorkspaceRef::Server(server_id));
+            }
+        }
+        if purged {
+            cascade.purged_ids.push(old.id.clone());
+        }
+    }
+    cascade
+}
+
+/// 트랜잭션 밖에서 정리 — 실패해도 워크스페이스 변경은 되돌리지 않고 로그만 남긴다
+fn apply_cascade(app: &tauri::AppHandle, cascade: &Cascade) {
+    if !cascade.alarm_refs.is_empty() {
+        if let Err(e) = alarm::clear_workspace_alarms(app, &cascade.alarm_refs) {
+            log::warn!("workspace: failed to clear alarms of deleted workspaces: {}", e);
+        }
+    }
+    if cascade.purged_ids.is_empty() {
+        return;
+    }
+    match storage::config_dir(app) {
+        Ok(config_dir) => remove_workspace_files(&config_dir, &cascade.purged_ids),
+        Err(e) => log::warn!("workspace: {}", e),
+    }
+}
+
+fn remove_workspace_files(config_dir: &Path, workspace_ids: &[String]) {
+    for id in workspace_ids {
+        let dir = match workspace_files_dir(config_dir, id) {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("workspace: {}", e);
+                continue;
+            }
+        };
+        if dir.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                log::warn!("workspace: failed to remove files of {}: {}", id, e);
+            }
+        }
+    }
+}
+
+fn workspace_files_dir(config_dir: &Path, workspace_id: &str) -> Result<PathBuf, String> {
+    if workspace_id.is_empty() || workspace_id.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid workspace id: {}", workspace_id));
+    }
+    Ok(config_dir.join(WORKSPACE_FILES_DIR).join(workspace_id))
+}
+
+/// 워크스페이스별 파일 디렉터리. 워크스페이스가 영구 삭제되면 함께 지워진다.
+pub fn files_dir(app: &tauri::AppHandle, workspace_id: &str) -> Result<PathBuf, String> {
+    workspace_files_dir(&storage::config_dir(app)?, workspace_id)
+}
+
+fn workspace_json(ws: &Workspace) -> serde_json::Value {
+    serde_json::to_value(ws).unwrap_or(serde_json::Value::Null)
+}
+
+/// 두 상태의 차이. 바뀐 것이 없으면 None (revision 은 호출한 쪽에서 채운다)
+fn diff_states(previous: &WorkspaceState, next: &WorkspaceState) -> Option<WorkspaceChangedEvent> {
+    let before: HashMap<&str, &Workspace> =
+        previous.workspaces.iter().map(|ws| (ws.id.as_str(), ws)).collect();
+    let after: HashSet<&str> = next.workspaces.iter().map(|ws| ws.id.as_str()).collect();
+
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for ws in &next.workspaces {
+        match before.get(ws.id.as_str()) {
+            None => added.push(ws.clone()),
+            Some(old) if workspace_json(old) != workspace_json(ws) => updated.push(ws.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed: Vec<String> = previous
+        .workspaces
+        .iter()
+        .filter(|ws| !after.contains(ws.id.as_str()))
+        .map(|ws| ws.id.clone())
+        .collect();
+
+    let current_changed = previous.current_workspace_id != next.current_workspace_id;
+    let other_changed = {
+        let strip = |state: &WorkspaceState| {
+            let mut value = serde_json::to_value(state).unwrap_or(serde_json::Value::Null);
+            if let Some(map) = value.as_object_mut() {
+                map.remove("workspaces");
+                map.remove("current_workspace_id");
+            }
+            value
+        };
+        strip(previous) != strip(next)
+    };
+
+    if added.is_empty() && updated.is_empty() && removed.is_empty() && !current_changed && !other_changed {
+        return None;
+    }
+    Some(WorkspaceChangedEvent {
+        revision: 0,
+        reason: WorkspaceChangeReason::Updated,
+        current_workspace_id: next.current_workspace_id.clone(),
+        previous_workspace_id: if current_changed {
+            previous.current_workspace_id.clone()
+        } else {
+            None
+        },
+        current_changed,
+        added,
+        updated,
+        removed,
+        other_changed,
+    })
+}
+
+fn emit_changed(app: &tauri::AppHandle, event: WorkspaceChangedEvent) {
+    if let Err(e) = app.emit(WORKSPACE_CHANGED_EVENT, event) {
+        log::warn!("workspace: failed to emit changed event: {}", e);
+    }
+}
+
+/// 가져오기 / 로그아웃처럼 상태 문서를 통째로 바꾼 뒤 호출. 리스너는 전체 상태를 다시 읽어야 한다.
+pub fn notify_reloaded(app: &tauri::AppHandle) -> Result<(), String> {
+    let revision = storage::transaction(app, |tx| {
+        let revision = tx.get::<u64>(WORKSPACE_REVISION_KEY)?.unwrap_or(0) + 1;
+        tx.put(WORKSPACE_REVISION_KEY, &revision)?;
+        Ok(revision)
+    })?;
+    let state = load_state(app);
+    emit_changed(
+        app,
+        WorkspaceChangedEvent {
+            revision,
+            reason: WorkspaceChangeReason::Reloaded,
+            current_workspace_id: state.current_workspace_id,
+            previous_workspace_id: None,
+            current_changed: true,
+            added: Vec::new(),
+            updated: Vec::new(),
+            removed: Vec::new(),
+            other_changed: true,
+        },
+    );
+    Ok(())
+}
+
+/// 타임스탬프 기반 id 를 새 id 로 바꾸고 상태 안의 모든 참조를 함께 갱신. 이전 id → 새 id 를 반환.
+/// 같은 밀리초에 만들어져 id 가 겹친 워크스페이스도 각각 새 id 를 받는다.
+fn migrate_legacy_ids(state: &mut WorkspaceState) -> HashMap<String, String> {
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for ws in state.workspaces.iter_mut() {
+        if !is_legacy_workspace_id(&ws.id) {
+            continue;
+        }
+        let new_id = new_workspace_id();
+        // 겹친 id 의 참조는 첫 번째 워크스페이스로 연결
+        renamed.entry(ws.id.clone()).or_insert_with(|| new_id.clone());
+        ws.id = new_id;
+    }
+    if renamed.is_empty() {
+        return renamed;
+    }
+
+    for id in [
+        state.current_workspace_id.as_mut(),
+        state.launch_workspace_id.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Some(new_id) = renamed.get(id) {
+            *id = new_id.clone();
+        }
+    }
+    for conflict in state.conflicts.iter_mut() {
+        if let Some(new_id) = renamed.get(&conflict.workspace_id) {
+            conflict.workspace_id = new_id.clone();
+        }
+    }
+    renamed
+}
+
+/// 상태 문서의 id 를 바꾸고 캐시 문서와 알람을 새 id 로 옮긴다. 워크스페이스가 사라진 것이 아니므로
+/// `update_state` 의 삭제 cascade 를 거치지 않는다.
+fn migrate_workspace_ids_in(tx: &storage::Tx) -> Result<HashMap<String, String>, String> {
+    let mut state = match tx.get::<WorkspaceState>(WORKSPACE_STATE_KEY)? {
+        Some(state) => state,
+        None => return Ok(HashMap::new()),
+    };
+    let renamed = migrate_legacy_ids(&mut state);
+    if renamed.is_empty() {
+        return Ok(renamed);
+    }
+    tx.put(WORKSPACE_STATE_KEY, &state)?;
+
+    for (old_id, new_id) in &renamed {
+        tx.rename_prefix(
+            &format!("{}:{}:", WORKSPACE_CACHE_PREFIX, old_id),
+            &format!("{}:{}:", WORKSPACE_CACHE_PREFIX, new_id),
+        )?;
+    }
+    if let Some(mut alarms) = tx.get::<alarm::AlarmManagerState>(alarm::ALARM_STATE_KEY)? {
+        if alarm::rename_workspace_refs(&mut alarms, &renamed) {
+            tx.put(alarm::ALARM_STATE_KEY, &alarms)?;
+        }
+    }
+    Ok(renamed)
+}
+
+fn move_workspace_files(config_dir: &Path, renamed: &HashMap<String, String>) {
+    for (old_id, new_id) in renamed {
+        let (from, to) = match (
+            workspace_files_dir(config_dir, old_id),
+            workspace_files_dir(config_dir, new_id),
+        ) {
+            (Ok(from), Ok(to)) => (from, to),
+            _ => continue,
+        };
+        if from.exists() && !to.exists() {
+            if let Err(e) = std::fs::rename(&from, &to) {
+                log::warn!("workspace: failed to move files of {}: {}", old_id, e);
+            }
+        }
+    }
+}
+
+/// setup 과 계정 데이터 가져오기 뒤에 호출 — 이전 형식 id 가 남아 있을 때만 저장소를 다시 쓴다.
+/// 알람 매니저가 메모리에 올라와 있으면 호출한 쪽에서 다시 읽어야 한다.
+pub fn migrate_workspace_ids(app: &tauri::AppHandle) -> Result<(), String> {
+    let renamed = storage::transaction(app, migrate_workspace_ids_in)?;
+    if renamed.is_empty() {
+        return Ok(());
+    }
+    log::info!("workspace: migrated {} legacy workspace ids", renamed.len());
+    match storage::config_dir(app) {
+        Ok(config_dir) => move_workspace_files(&config_dir, &renamed),
+        Err(e) => log::warn!("workspace: {}", e),
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -192,23 +814,56 @@ pub fn get_workspace_state(app: tauri::AppHandle) -> WorkspaceState {
     load_state(&app)
 }
 
+/// 상태와 revision 을 한 트랜잭션에서 읽음 — 이벤트를 놓친 리스너의 재동기화용
 #[tauri::command]
-pub fn list_personal_workspaces(app: tauri::AppHandle) -> Vec<Workspace> {
-    let state = load_state(&app);
-    state
+pub fn get_workspace_snapshot(app: tauri::AppHandle) -> Result<WorkspaceSnapshot, String> {
+    storage::transaction(&app, |tx| {
+        let state = ensure_valid_state(
+            tx.get::<WorkspaceState>(WORKSPACE_STATE_KEY)?
+                .unwrap_or_else(empty_state),
+        );
+        let revision = tx.get::<u64>(WORKSPACE_REVISION_KEY)?.unwrap_or(0);
+        Ok(WorkspaceSnapshot { revision, state })
+    })
+}
+
+fn list_workspaces_of_type(
+    app: &tauri::AppHandle,
+    workspace_type: WorkspaceType,
+    include_archived: bool,
+) -> Vec<Workspace> {
+    let state = load_state(app);
+    let mut workspaces: Vec<Workspace> = state
         .workspaces
         .into_iter()
-        .filter(|ws| ws.workspace_type == WorkspaceType::Personal)
-        .collect()
+        .filter(|ws| ws.workspace_type == workspace_type)
+        .filter(|ws| ws.deleted_at_unix.is_none())
+        .filter(|ws| include_archived || ws.archived_at_unix.is_none())
+        .collect();
+    sort_for_display(&mut workspaces);
+    workspaces
+}
+
+#[tauri::command]
+pub fn list_personal_workspaces(
+    app: tauri::AppHandle,
+    include_archived: Option<bool>,
+) -> Vec<Workspace> {
+    list_workspaces_of_type(&app, WorkspaceType::Personal, include_archived.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn list_team_workspaces(app: tauri::AppHandle, include_archived: Option<bool>) -> Vec<Workspace> {
+    list_workspaces_of_type(&app, WorkspaceType::Team, include_archived.unwrap_or(false))
 }
 
 #[tauri::command]
-pub fn list_team_workspaces(app: tauri::AppHandle) -> Vec<Workspace> {
+pub fn list_trashed_workspaces(app: tauri::AppHandle) -> Vec<Workspace> {
     let state = load_state(&app);
     state
         .workspaces
         .into_iter()
-        .filter(|ws| ws.workspace_type == WorkspaceType::Team)
+        .filter(|ws| ws.deleted_at_unix.is_some())
         .collect()
 }
 
@@ -227,11 +882,13 @@ pub fn switch_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<W
     }
 
     let state = update_state(&app, |state| {
-        if state.workspaces.iter().any(|ws| ws.id == workspace_id) {
-            state.current_workspace_id = Some(workspace_id.to_string());
-            Ok(())
-        } else {
-            Err("Workspace not found".to_string())
+        match state.workspaces.iter().find(|ws| ws.id == workspace_id) {
+            Some(ws) if is_active(ws) => {
+                state.current_workspace_id = Some(workspace_id.to_string());
+                Ok(())
+            }
+            Some(_) => Err("Workspace is archived or in the trash".to_string()),
+            None => Err("Workspace not found".to_string()),
         }
     })?;
 
@@ -243,24 +900,78 @@ pub fn switch_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<W
         .ok_or_else(|| "Workspace not found".to_string())
 }
 
-#[tauri::command]
-pub fn create_workspace(app: tauri::AppHandle, name: String) -> Result<Workspace, String> {
-    let name = validate_workspace_name(&name)?;
-    let mut created: Option<Workspace> = None;
-
-    update_state(&app, |state| {
-        let now = now_unix();
-        let workspace = Workspace {
-            id: new_workspace_id(),
-            name: name.clone(),
-            workspace_type: WorkspaceType::Personal,
-            plan_tier: None,
-            team_id: None,
-            created_at_unix: now,
-            updated_at_unix: now,
-        };
+/// 휴지통에 있는 워크스페이스는 한도 계산에서 제외
+fn count_workspaces(state: &WorkspaceState, workspace_type: WorkspaceType) -> usize {
+    state
+        .workspaces
+        .iter()
+        .filter(|ws| ws.workspace_type == workspace_type && ws.deleted_at_unix.is_none())
+        .count()
+}
+
+/// 휴지통에 없는 팀 워크스페이스의 멤버 목록
+fn active_team_rosters(state: &WorkspaceState) -> impl Iterator<Item = &TeamRoster> {
+    let team_ids: HashSet<i64> = state
+        .workspaces
+        .iter()
+        .filter(|ws| ws.workspace_type == WorkspaceType::Team && ws.deleted_at_unix.is_none())
+        .filter_map(|ws| ws.team_id)
+        .collect();
+    state
+        .team_rosters
+        .iter()
+        .filter(move |roster| team_ids.contains(&roster.team_id))
+}
+
+/// 휴지통에 없는 팀 워크스페이스의 멤버 수. 여러 팀에 속한 멤버는 한 좌석으로 센다
+pub fn count_team_seats(state: &WorkspaceState) -> usize {
+    active_team_rosters(state)
+        .flat_map(|roster| roster.members.iter().map(|m| m.member_id))
+        .collect::<HashSet<i64>>()
+        .len()
+}
+
+/// 휴지통에 없는 팀 워크스페이스 중 한 곳에라도 이 이메일의 멤버가 있으면 이미 좌석을 쓰고 있다
+pub fn holds_team_seat(state: &WorkspaceState, email: &str) -> bool {
+    active_team_rosters(state).any(|roster| {
+        roster
+            .members
+            .iter()
+            .any(|m| m.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(email)))
+    })
+}
+
+/// 새 팀을 만들 때 늘어나는 좌석 수. 이미 다른 팀에 속해 있으면 만든 사람은 좌석을 더 쓰지 않는다
+fn creator_new_seats(state: &WorkspaceState) -> usize {
+    if active_team_rosters(state).any(|roster| roster.my_role.is_some()) {
+        0
+    } else {
+        1
+    }
+}
+
+/// 한도를 확인한 뒤 개인 워크스페이스를 만들고 현재 워크스페이스로 전환.
+/// 한도를 넘으면 `UpgradeRequired` JSON 을 에러 문자열로 반환
+pub async fn create_personal_workspace(
+    app: &tauri::AppHandle,
+    name: &str,
+    settings: WorkspaceSettings,
+) -> Result<Workspace, String> {
+    let name = validate_workspace_name(name)?;
+    let settings = validate_settings(settings)?;
+    let entitlements = entitlement::current(app, false).await;
+    let mut created: Option<Workspace> = None;
+
+    update_state(app, |state| {
+        entitlement::check_personal_workspace(
+            &entitlements,
+            count_workspaces(state, WorkspaceType::Personal),
+        )
+        .map_err(|e| e.to_error())?;
+        let mut workspace = new_workspace(name, WorkspaceType::Personal);
+        workspace.settings = settings;
+        let workspace = push_workspace(state, workspace);
         state.current_workspace_id = Some(workspace.id.clone());
-        state.workspaces.push(workspace.clone());
         created = Some(workspace);
         Ok(())
     })?;
@@ -268,6 +979,37 @@ pub fn create_workspace(app: tauri::AppHandle, name: String) -> Result<Workspace
     created.ok_or_else(|| "Failed to create workspace".to_string())
 }
 
+#[tauri::command]
+pub async fn create_workspace(app: tauri::AppHandle, name: String) -> Result<Workspace, String> {
+    create_personal_workspace(&app, &name, WorkspaceSettings::default()).await
+}
+
+/// 설정, 그룹, 고정 여부를 복사한 개인 워크스페이스를 만든다. 팀 워크스페이스도 개인 사본으로 복제된다.
+/// 태그와 반복 일정은 서버에 있으므로 `workspace_template::duplicate_workspace` 가 함께 돌려준다.
+pub async fn copy_workspace(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+    name: Option<String>,
+) -> Result<Workspace, String> {
+    let source = load_state(app)
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id.trim() && ws.deleted_at_unix.is_none())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    let name = match name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("{} 사본", source.name.chars().take(47).collect::<String>()),
+    };
+
+    let created = create_personal_workspace(app, &name, source.settings.clone()).await?;
+    modify_workspace(app, &created.id, |state| {
+        let ws = find_mut(state, &created.id)?;
+        ws.group_id = source.group_id.clone();
+        ws.pinned = source.pinned;
+        Ok(())
+    })
+}
+
 #[tauri::command]
 pub fn rename_workspace(
     app: tauri::AppHandle,
@@ -278,13 +1020,14 @@ pub fn rename_workspace(
     let mut updated: Option<Workspace> = None;
 
     update_state(&app, |state| {
+        ensure_team_permission(state, &workspace_id, TeamAction::Rename)?;
         let ws = state
             .workspaces
             .iter_mut()
             .find(|ws| ws.id == workspace_id)
             .ok_or_else(|| "Workspace not found".to_string())?;
         ws.name = name.clone();
-        ws.updated_at_unix = now_unix();
+        ws.updated_at_unix = account::now_unix();
         updated = Some(ws.clone());
         Ok(())
     })?;
@@ -292,93 +1035,731 @@ pub fn rename_workspace(
     updated.ok_or_else(|| "Failed to rename workspace".to_string())
 }
 
+pub fn team_role(state: &WorkspaceState, team_id: i64) -> Option<TeamRole> {
+    state
+        .team_rosters
+        .iter()
+        .find(|r| r.team_id == team_id)
+        .and_then(|r| r.my_role)
+}
+
+/// 팀 워크스페이스는 역할에 따라 이름 변경 / 삭제를 제한한다.
+/// 아직 서버 팀에 연결되지 않은 워크스페이스는 이 기기에서 만든 것이므로 허용.
+fn ensure_team_permission(state: &WorkspaceState, workspace_id: &str, action: TeamAction) -> Result<(), String> {
+    let team_id = match state.workspaces.iter().find(|ws| ws.id == workspace_id) {
+        Some(ws) if ws.workspace_type == WorkspaceType::Team => match ws.team_id {
+            Some(team_id) => team_id,
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    let role = team_role(state, team_id)
+        .ok_or_else(|| "Team role is unknown; refresh team members first".to_string())?;
+    let allowed = match action {
+        TeamAction::Rename => role.can_manage(),
+        TeamAction::Delete => role.can_delete(),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err("You do not have permission to change this team workspace".to_string())
+    }
+}
+
+/// 워크스페이스 하나를 찾아 수정하고 수정된 사본을 반환
+fn modify_workspace(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+    f: impl FnOnce(&mut WorkspaceState) -> Result<(), String>,
+) -> Result<Workspace, String> {
+    let workspace_id = workspace_id.trim();
+    if workspace_id.is_empty() {
+        return Err("workspace_id is required".to_string());
+    }
+
+    let state = update_state(app, |state| {
+        if !state.workspaces.iter().any(|ws| ws.id == workspace_id) {
+            return Err("Workspace not found".to_string());
+        }
+        f(state)
+    })?;
+    state
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| "Workspace not found".to_string())
+}
+
+fn find_mut<'a>(state: &'a mut WorkspaceState, workspace_id: &str) -> Result<&'a mut Workspace, String> {
+    state
+        .workspaces
+        .iter_mut()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| "Workspace not found".to_string())
+}
+
+/// 휴지통으로 이동 (soft delete). `restore_workspace` 로 되돌리거나 `purge_workspace` 로 영구 삭제.
 #[tauri::command]
-pub fn delete_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<(), String> {
+pub fn delete_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        ensure_other_active_personal(state, id)?;
+        ensure_team_permission(state, id, TeamAction::Delete)?;
+        let ws = find_mut(state, id)?;
+        if ws.deleted_at_unix.is_some() {
+            return Err("Workspace is already in the trash".to_string());
+        }
+        let now = account::now_unix();
+        ws.deleted_at_unix = Some(now);
+        ws.updated_at_unix = now;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn restore_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        let ws = find_mut(state, id)?;
+        if ws.deleted_at_unix.is_none() {
+            return Err("Workspace is not in the trash".to_string());
+        }
+        ws.deleted_at_unix = None;
+        ws.updated_at_unix = account::now_unix();
+        Ok(())
+    })
+}
+
+/// 휴지통에 있는 워크스페이스를 즉시 영구 삭제
+#[tauri::command]
+pub fn purge_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<(), String> {
     let workspace_id = workspace_id.trim();
     if workspace_id.is_empty() {
         return Err("workspace_id is required".to_string());
     }
 
     update_state(&app, |state| {
-        let target = state
+        let ws = state
             .workspaces
             .iter()
             .find(|ws| ws.id == workspace_id)
-            .cloned()
             .ok_or_else(|| "Workspace not found".to_string())?;
+        if ws.deleted_at_unix.is_none() {
+            return Err("Only workspaces in the trash can be purged".to_string());
+        }
+        ensure_team_permission(state, workspace_id, TeamAction::Delete)?;
+        purge_from_state(state, workspace_id);
+        Ok(())
+    })?;
+    Ok(())
+}
 
-        if target.workspace_type == WorkspaceType::Personal {
-            let personal_count = state
+#[tauri::command]
+pub fn archive_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        ensure_other_active_personal(state, id)?;
+        let ws = find_mut(state, id)?;
+        if ws.deleted_at_unix.is_some() {
+            return Err("Workspace is in the trash".to_string());
+        }
+        let now = account::now_unix();
+        ws.archived_at_unix.get_or_insert(now);
+        ws.updated_at_unix = now;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unarchive_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        let ws = find_mut(state, id)?;
+        ws.archived_at_unix = None;
+        ws.updated_at_unix = account::now_unix();
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_trash_retention_days(app: tauri::AppHandle, days: u32) -> Result<u32, String> {
+    if !(1..=365).contains(&days) {
+        return Err("Trash retention must be between 1 and 365 days".to_string());
+    }
+    let state = update_state(&app, |state| {
+        state.trash_retention_days = days;
+        Ok(())
+    })?;
+    Ok(state.trash_retention_days)
+}
+
+fn new_group_id() -> String {
+    format!("grp_{}", Uuid::now_v7().simple())
+}
+
+fn validate_group_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    let len = trimmed.chars().count();
+    if len == 0 {
+        return Err("Group name is required".to_string());
+    }
+    if len > 30 {
+        return Err("Group name must be 30 characters or less".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+/// 휴지통에 없는 워크스페이스를 모두 한 번씩 받아 그 순서대로 표시 순서를 다시 매김
+#[tauri::command]
+pub fn reorder_workspaces(
+    app: tauri::AppHandle,
+    ordered_ids: Vec<String>,
+) -> Result<WorkspaceState, String> {
+    update_state(&app, |state| {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for id in &ordered_ids {
+            if !seen.insert(id.as_str()) {
+                return Err(format!("Workspace {} is listed twice", id));
+            }
+            if !state
                 .workspaces
                 .iter()
-                .filter(|ws| ws.workspace_type == WorkspaceType::Personal)
-                .count();
-            if personal_count <= 1 {
-                return Err("At least one personal workspace is required".to_string());
+                .any(|ws| &ws.id == id && ws.deleted_at_unix.is_none())
+            {
+                return Err(format!("Workspace {} not found", id));
             }
         }
+        let active = state
+            .workspaces
+            .iter()
+            .filter(|ws| ws.deleted_at_unix.is_none())
+            .count();
+        if ordered_ids.len() != active {
+            return Err("Every workspace must be listed exactly once".to_string());
+        }
 
-        state.workspaces.retain(|ws| ws.id != workspace_id);
+        // 휴지통의 워크스페이스는 기존 순서대로 뒤에 둔다
+        let mut rest: Vec<&mut Workspace> = Vec::new();
+        for ws in state.workspaces.iter_mut() {
+            match ordered_ids.iter().position(|id| id == &ws.id) {
+                Some(position) => ws.sort_order = position as i64,
+                None => rest.push(ws),
+            }
+        }
+        rest.sort_by_key(|ws| ws.sort_order);
+        for (offset, ws) in rest.into_iter().enumerate() {
+            ws.sort_order = (ordered_ids.len() + offset) as i64;
+        }
+        Ok(())
+    })
+}
 
-        if state.current_workspace_id.as_deref() == Some(workspace_id) {
-            state.current_workspace_id = state.workspaces.first().map(|ws| ws.id.clone());
+#[tauri::command]
+pub fn set_workspace_pinned(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    pinned: bool,
+) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        let ws = find_mut(state, id)?;
+        ws.pinned = pinned;
+        ws.updated_at_unix = account::now_unix();
+        Ok(())
+    })
+}
+
+/// 앱 시작 시 열 워크스페이스 지정. `None` 이면 마지막으로 사용한 워크스페이스로 시작한다.
+#[tauri::command]
+pub fn set_launch_workspace(
+    app: tauri::AppHandle,
+    workspace_id: Option<String>,
+) -> Result<WorkspaceState, String> {
+    update_state(&app, |state| {
+        if let Some(id) = workspace_id.as_deref() {
+            match state.workspaces.iter().find(|ws| ws.id == id) {
+                Some(ws) if is_active(ws) => {}
+                Some(_) => return Err("Workspace is archived or in the trash".to_string()),
+                None => return Err("Workspace not found".to_string()),
+            }
         }
+        state.launch_workspace_id = workspace_id;
+        Ok(())
+    })
+}
+
+/// setup 에서 호출 — 시작 워크스페이스가 지정되어 있으면 그 워크스페이스로 전환
+pub fn apply_launch_workspace(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = load_state(app);
+    if state.launch_workspace_id.is_none()
+        || state.launch_workspace_id == state.current_workspace_id
+    {
+        return Ok(());
+    }
+    update_state(app, |state| {
+        state.current_workspace_id = state.launch_workspace_id.clone();
+        Ok(())
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn create_workspace_group(app: tauri::AppHandle, name: String) -> Result<WorkspaceGroup, String> {
+    let name = validate_group_name(&name)?;
+    let mut created: Option<WorkspaceGroup> = None;
+    update_state(&app, |state| {
+        let group = WorkspaceGroup {
+            id: new_group_id(),
+            name,
+            sort_order: state.groups.iter().map(|g| g.sort_order + 1).max().unwrap_or(0),
+            collapsed: false,
+        };
+        state.groups.push(group.clone());
+        created = Some(group);
         Ok(())
     })?;
+    created.ok_or_else(|| "Failed to create group".to_string())
+}
 
+#[tauri::command]
+pub fn rename_workspace_group(
+    app: tauri::AppHandle,
+    group_id: String,
+    name: String,
+) -> Result<WorkspaceGroup, String> {
+    let name = validate_group_name(&name)?;
+    let state = update_state(&app, |state| {
+        let group = state
+            .groups
+            .iter_mut()
+            .find(|g| g.id == group_id)
+            .ok_or_else(|| "Group not found".to_string())?;
+        group.name = name;
+        Ok(())
+    })?;
+    state
+        .groups
+        .into_iter()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| "Group not found".to_string())
+}
+
+/// 그룹 삭제. 그룹에 있던 워크스페이스는 그룹 밖으로 빠질 뿐 삭제되지 않는다.
+#[tauri::command]
+pub fn delete_workspace_group(app: tauri::AppHandle, group_id: String) -> Result<(), String> {
+    update_state(&app, |state| {
+        if !state.groups.iter().any(|g| g.id == group_id) {
+            return Err("Group not found".to_string());
+        }
+        state.groups.retain(|g| g.id != group_id);
+        for ws in state.workspaces.iter_mut() {
+            if ws.group_id.as_deref() == Some(group_id.as_str()) {
+                ws.group_id = None;
+            }
+        }
+        Ok(())
+    })?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_team_workspace_creation_path(plan_tier: String) -> TeamWorkspaceCreationPath {
-    if is_paid_plan(&plan_tier) {
-        TeamWorkspaceCreationPath {
+pub fn reorder_workspace_groups(
+    app: tauri::AppHandle,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<WorkspaceGroup>, String> {
+    let mut state = update_state(&app, |state| {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for id in &ordered_ids {
+            if !seen.insert(id.as_str()) {
+                return Err(format!("Group {} is listed twice", id));
+            }
+            if !state.groups.iter().any(|g| &g.id == id) {
+                return Err(format!("Group {} not found", id));
+            }
+        }
+        if ordered_ids.len() != state.groups.len() {
+            return Err("Every group must be listed exactly once".to_string());
+        }
+
+        for group in state.groups.iter_mut() {
+            if let Some(position) = ordered_ids.iter().position(|id| id == &group.id) {
+                group.sort_order = position as i64;
+            }
+        }
+        Ok(())
+    })?;
+    state.groups.sort_by_key(|g| g.sort_order);
+    Ok(state.groups)
+}
+
+#[tauri::command]
+pub fn move_workspace_to_group(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    group_id: Option<String>,
+) -> Result<Workspace, String> {
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        if let Some(group_id) = group_id.as_deref() {
+            if !state.groups.iter().any(|g| g.id == group_id) {
+                return Err("Group not found".to_string());
+            }
+        }
+        let ws = find_mut(state, id)?;
+        ws.group_id = group_id;
+        ws.updated_at_unix = account::now_unix();
+        Ok(())
+    })
+}
+
+const MAX_REMINDER_MINUTES: u32 = 4 * 7 * 24 * 60;
+
+/// `#RGB` / `#RRGGBB` 를 소문자 `#rrggbb` 로 정규화
+pub fn normalize_color(field: &str, color: Option<String>) -> Result<Option<String>, String> {
+    let color = match color.as_deref().map(str::trim) {
+        Some(c) if !c.is_empty() => c.to_ascii_lowercase(),
+        _ => return Ok(None),
+    };
+    let hex = color
+        .strip_prefix('#')
+        .filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("{} must be a hex color like #1a73e8", field))?;
+    match hex.len() {
+        6 => Ok(Some(color)),
+        3 => Ok(Some(format!(
+            "#{}",
+            hex.chars().flat_map(|c| [c, c]).collect::<String>()
+        ))),
+        _ => Err(format!("{} must be a hex color like #1a73e8", field)),
+    }
+}
+
+fn parse_timezone(timezone: &str) -> Result<Tz, String> {
+    timezone
+        .parse::<Tz>()
+        .map_err(|_| format!("Unknown timezone: {}", timezone))
+}
+
+pub fn validate_settings(settings: WorkspaceSettings) -> Result<WorkspaceSettings, String> {
+    let icon = match settings.icon.as_deref().map(str::trim) {
+        Some(icon) if !icon.is_empty() => {
+            if icon.chars().count() > 32 {
+                return Err("Icon must be 32 characters or less".to_string());
+            }
+            Some(icon.to_string())
+        }
+        _ => None,
+    };
+    if settings
+        .default_reminder_minutes
+        .is_some_and(|m| m > MAX_REMINDER_MINUTES)
+    {
+        return Err("Default reminder must be within 4 weeks".to_string());
+    }
+    let timezone = match settings.timezone.as_deref().map(str::trim) {
+        Some(tz) if !tz.is_empty() => Some(parse_timezone(tz)?.name().to_string()),
+        _ => None,
+    };
+
+    Ok(WorkspaceSettings {
+        color: normalize_color("Color", settings.color)?,
+        icon,
+        default_view: settings.default_view,
+        default_task_color: normalize_color("Default task color", settings.default_task_color)?,
+        default_reminder_minutes: settings.default_reminder_minutes,
+        timezone,
+    })
+}
+
+/// 워크스페이스 설정 전체를 교체
+#[tauri::command]
+pub fn update_workspace_settings(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    settings: WorkspaceSettings,
+) -> Result<Workspace, String> {
+    let settings = validate_settings(settings)?;
+    let id = workspace_id.trim();
+    modify_workspace(&app, id, |state| {
+        let ws = find_mut(state, id)?;
+        ws.settings = settings;
+        ws.updated_at_unix = account::now_unix();
+        Ok(())
+    })
+}
+
+/// 프론트엔드가 일정 시각을 워크스페이스 타임존 기준으로 그릴 수 있도록 현재 오프셋을 계산
+#[tauri::command]
+pub fn get_workspace_time_zone(
+    app: tauri::AppHandle,
+    workspace_id: String,
+) -> Result<WorkspaceTimeZone, String> {
+    let state = load_state(&app);
+    let ws = state
+        .workspaces
+        .iter()
+        .find(|ws| ws.id == workspace_id.trim())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+
+    let now = Utc::now();
+    let local_utc_offset_minutes = now.with_timezone(&Local).offset().fix().local_minus_utc() / 60;
+    let utc_offset_minutes = match ws.settings.timezone.as_deref() {
+        Some(tz) => now.with_timezone(&parse_timezone(tz)?).offset().fix().local_minus_utc() / 60,
+        None => local_utc_offset_minutes,
+    };
+
+    Ok(WorkspaceTimeZone {
+        workspace_id: ws.id.clone(),
+        timezone: ws.settings.timezone.clone(),
+        utc_offset_minutes,
+        local_utc_offset_minutes,
+        differs_from_local: utc_offset_minutes != local_utc_offset_minutes,
+    })
+}
+
+fn team_creation_path(entitlements: &Entitlements, state: &WorkspaceState) -> TeamWorkspaceCreationPath {
+    match entitlement::check_team_workspace(
+        entitlements,
+        count_workspaces(state, WorkspaceType::Team),
+        count_team_seats(state),
+        creator_new_seats(state),
+    ) {
+        Ok(()) => TeamWorkspaceCreationPath {
             status: "allowed".to_string(),
             route: "/workspace/new/team".to_string(),
             message: "현재 플랜에서 팀 워크스페이스를 생성할 수 있습니다.".to_string(),
-        }
-    } else {
-        TeamWorkspaceCreationPath {
+            upgrade: None,
+        },
+        Err(upgrade) => TeamWorkspaceCreationPath {
             status: "upgrade_required".to_string(),
-            route: "/billing/upgrade?target=team_workspace".to_string(),
-            message: "팀 워크스페이스 생성을 위해 유료 플랜 업그레이드가 필요합니다.".to_string(),
-        }
+            route: upgrade.route.clone(),
+            message: upgrade.message.clone(),
+            upgrade: Some(upgrade),
+        },
     }
 }
 
 #[tauri::command]
-pub fn create_team_workspace(
+pub async fn get_team_workspace_creation_path(app: tauri::AppHandle) -> TeamWorkspaceCreationPath {
+    let entitlements = entitlement::current(&app, false).await;
+    team_creation_path(&entitlements, &load_state(&app))
+}
+
+/// 한도를 넘으면 워크스페이스를 만들지 않고 `status: "upgrade_required"` 로 응답
+#[tauri::command]
+pub async fn create_team_workspace(
     app: tauri::AppHandle,
     name: String,
-    plan_tier: String,
     team_id: Option<i64>,
 ) -> Result<TeamWorkspaceCreationResult, String> {
     let name = validate_workspace_name(&name)?;
-    let normalized_plan = normalize_plan(&plan_tier);
+    let entitlements = entitlement::current(&app, false).await;
 
     let mut created: Option<Workspace> = None;
+    let mut blocked: Option<TeamWorkspaceCreationPath> = None;
     update_state(&app, |state| {
-        let now = now_unix();
-        let resolved_team_id = team_id.unwrap_or_else(new_team_id);
-        let workspace = Workspace {
-            id: new_workspace_id(),
-            name: name.clone(),
-            workspace_type: WorkspaceType::Team,
-            plan_tier: Some(normalized_plan.clone()),
-            team_id: Some(resolved_team_id),
-            created_at_unix: now,
-            updated_at_unix: now,
-        };
+        let path = team_creation_path(&entitlements, state);
+        if path.upgrade.is_some() {
+            blocked = Some(path);
+            return Ok(());
+        }
+        // 팀 id 는 서버가 정한다. 모르면 비워 두고 동기화 때 채운다.
+        let mut workspace = new_workspace(name.clone(), WorkspaceType::Team);
+        workspace.plan_tier = Some(entitlements.plan_name.clone());
+        workspace.team_id = team_id;
+        let workspace = push_workspace(state, workspace);
         state.current_workspace_id = Some(workspace.id.clone());
-        state.workspaces.push(workspace.clone());
         created = Some(workspace);
         Ok(())
     })?;
 
+    if let Some(path) = blocked {
+        return Ok(TeamWorkspaceCreationResult {
+            status: path.status,
+            message: path.message,
+            route: path.route,
+            workspace: None,
+            upgrade: path.upgrade,
+        });
+    }
+
     Ok(TeamWorkspaceCreationResult {
         status: "created".to_string(),
         message: "팀 워크스페이스가 생성되었습니다.".to_string(),
         route: "/workspace".to_string(),
         workspace: created,
+        upgrade: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_SECS: i64 = 24 * 60 * 60;
+
+    fn test_config_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("deskcal-test-{}", Uuid::now_v7().simple()))
+    }
+
+    #[test]
+    fn expired_trash_is_purged_with_cache_and_files() {
+        let db = storage::Database::open_in_memory().unwrap();
+        let config_dir = test_config_dir();
+
+        let mut state = empty_state();
+        let active = push_workspace(&mut state, default_personal_workspace());
+        let mut trashed = new_workspace("old".to_string(), WorkspaceType::Personal);
+        trashed.deleted_at_unix = Some(account::now_unix() - 31 * DAY_SECS);
+        let expired = push_workspace(&mut state, trashed);
+        state.current_workspace_id = Some(active.id.clone());
+
+        let expired_key = format!("{}:{}:tasks", WORKSPACE_CACHE_PREFIX, expired.id);
+        let active_key = format!("{}:{}:tasks", WORKSPACE_CACHE_PREFIX, active.id);
+        let expired_files = workspace_files_dir(&config_dir, &expired.id).unwrap();
+        std::fs::create_dir_all(&expired_files).unwrap();
+        std::fs::write(expired_files.join("note.txt"), b"note").unwrap();
+
+        let cascade = db
+            .transaction(|tx| {
+                tx.put(WORKSPACE_STATE_KEY, &state)?;
+                tx.put(&expired_key, &1)?;
+                tx.put(&active_key, &1)?;
+                update_state_in(tx, |_| Ok(())).map(|(_, _, cascade)| cascade)
+            })
+            .unwrap();
+        remove_workspace_files(&config_dir, &cascade.purged_ids);
+
+        assert_eq!(cascade.purged_ids, vec![expired.id.clone()]);
+        db.transaction(|tx| {
+            assert!(tx.get_raw(&expired_key)?.is_none());
+            assert!(tx.get_raw(&active_key)?.is_some());
+            let stored: WorkspaceState = tx.get(WORKSPACE_STATE_KEY)?.unwrap();
+            assert!(stored.workspaces.iter().all(|ws| ws.id != expired.id));
+            Ok(())
+        })
+        .unwrap();
+        assert!(!expired_files.exists());
+        let _ = std::fs::remove_dir_all(&config_dir);
+    }
+
+    #[test]
+    fn legacy_id_migration_moves_cache_alarms_and_files() {
+        let db = storage::Database::open_in_memory().unwrap();
+        let config_dir = test_config_dir();
+
+        let mut state = empty_state();
+        let mut legacy = new_workspace("legacy".to_string(), WorkspaceType::Personal);
+        legacy.id = "ws_1700000000000".to_string();
+        let legacy = push_workspace(&mut state, legacy);
+        state.current_workspace_id = Some(legacy.id.clone());
+        state.launch_workspace_id = Some(legacy.id.clone());
+
+        let legacy_ref = WorkspaceRef::Local(legacy.id.clone());
+        let alarms = alarm::AlarmManagerState {
+            notifications_enabled: true,
+            alarms: vec![alarm::AlarmRecord {
+                alarm_id: format!("task:{}:7:100", legacy_ref),
+                task_id: 7,
+                workspace_id: legacy_ref,
+                title: "standup".to_string(),
+                start_at_unix: 100,
+                trigger_at_unix: 40,
+                next_trigger_at_unix: None,
+                status: alarm::AlarmStatus::Pending,
+                is_enabled: true,
+                reminder_minutes_before: 1,
+                last_triggered_at_unix: None,
+                created_at_unix: 0,
+                updated_at_unix: 0,
+            }],
+        };
+        let legacy_key = format!("{}:{}:tasks", WORKSPACE_CACHE_PREFIX, legacy.id);
+        let legacy_files = workspace_files_dir(&config_dir, &legacy.id).unwrap();
+        std::fs::create_dir_all(&legacy_files).unwrap();
+        std::fs::write(legacy_files.join("note.txt"), b"note").unwrap();
+
+        let renamed = db
+            .transaction(|tx| {
+                tx.put(WORKSPACE_STATE_KEY, &state)?;
+                tx.put(alarm::ALARM_STATE_KEY, &alarms)?;
+                tx.put(&legacy_key, &1)?;
+                migrate_workspace_ids_in(tx)
+            })
+            .unwrap();
+        move_workspace_files(&config_dir, &renamed);
+
+        let new_id = renamed.get(&legacy.id).unwrap().clone();
+        assert!(!is_legacy_workspace_id(&new_id));
+        db.transaction(|tx| {
+            let stored: WorkspaceState = tx.get(WORKSPACE_STATE_KEY)?.unwrap();
+            assert_eq!(stored.workspaces[0].id, new_id);
+            assert_eq!(stored.current_workspace_id.as_deref(), Some(new_id.as_str()));
+            assert_eq!(stored.launch_workspace_id.as_deref(), Some(new_id.as_str()));
+
+            assert!(tx.get_raw(&legacy_key)?.is_none());
+            let moved_key = format!("{}:{}:tasks", WORKSPACE_CACHE_PREFIX, new_id);
+            assert!(tx.get_raw(&moved_key)?.is_some());
+
+            let alarms: alarm::AlarmManagerState = tx.get(alarm::ALARM_STATE_KEY)?.unwrap();
+            assert_eq!(alarms.alarms[0].workspace_id, WorkspaceRef::Local(new_id.clone()));
+            assert_eq!(alarms.alarms[0].alarm_id, format!("task:{}:7:100", new_id));
+            Ok(())
+        })
+        .unwrap();
+        assert!(!legacy_files.exists());
+        assert!(workspace_files_dir(&config_dir, &new_id)
+            .unwrap()
+            .join("note.txt")
+            .exists());
+        let _ = std::fs::remove_dir_all(&config_dir);
+    }
+
+    #[test]
+    fn team_creation_counts_the_creator_seat_once() {
+        let entitlements = Entitlements {
+            plan_name: "pro".to_string(),
+            is_paid: true,
+            subscription_status: Some("active".to_string()),
+            subscription_expires_at: None,
+            max_personal_workspaces: None,
+            max_team_workspaces: Some(3),
+            team_seats: Some(2),
+            storage_quota_bytes: None,
+            storage_used_bytes: None,
+            fetched_at_unix: 0,
+            expires_at_unix: 0,
+        };
+        let mut state = empty_state();
+        assert_eq!(team_creation_path(&entitlements, &state).status, "allowed");
+
+        let mut team = new_workspace("team".to_string(), WorkspaceType::Team);
+        team.team_id = Some(7);
+        push_workspace(&mut state, team);
+        let member = |member_id| TeamMember {
+            member_id,
+            role: TeamRole::Member,
+            nickname: None,
+            email: None,
+            joined_at: None,
+        };
+        state.team_rosters.push(TeamRoster {
+            team_id: 7,
+            members: vec![member(1), member(2)],
+            my_role: None,
+            fetched_at_unix: 0,
+        });
+
+        // 좌석 두 개를 다 쓴 팀에 속하지 않은 사용자가 팀을 만들면 좌석이 하나 더 필요하다
+        let path = team_creation_path(&entitlements, &state);
+        assert_eq!(path.status, "upgrade_required");
+        let upgrade = path.upgrade.unwrap();
+        assert_eq!(upgrade.limit, entitlement::EntitlementLimit::TeamSeats);
+        assert_eq!((upgrade.current, upgrade.max), (2, 2));
+
+        // 이미 그 팀의 멤버라면 새 팀을 만들어도 좌석이 늘지 않는다
+        state.team_rosters[0].my_role = Some(TeamRole::Owner);
+        assert_eq!(team_creation_path(&entitlements, &state).status, "allowed");
+    }
+}
@@ -0,0 +1,506 @@
+/// Workspace synchronisation with the server `/api/me/workspaces` API.
+///
+/// Local workspaces keep their own string ids; `Workspace.server_id` links them to the
+/// numeric server id. Names are merged three-way against `synced_name` (the server name at
+/// the last sync), offline-created workspaces are pushed on the next sync and anything
+/// that cannot be merged automatically is recorded in `WorkspaceState.conflicts`.
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::workspace::{
+    self, Workspace, WorkspaceConflict, WorkspaceConflictKind, WorkspaceState, WorkspaceSyncState,
+    WorkspaceType,
+};
+use crate::{account, api, workspace_team};
+
+const WORKSPACES_ENDPOINT: &str = "/api/me/workspaces";
+const CREATE_WORKSPACE_ENDPOINT: &str = "/api/workspaces";
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServerWorkspace {
+    workspace_id: i64,
+    #[serde(rename = "type")]
+    workspace_type: WorkspaceType,
+    owner_id: i64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerWorkspacesResponse {
+    #[serde(default)]
+    workspaces: Vec<ServerWorkspace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatedWorkspaceResponse {
+    workspace: ServerWorkspace,
+}
+
+#[derive(Serialize)]
+struct CreateWorkspaceRequest<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    workspace_type: &'a WorkspaceType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct RenameWorkspaceRequest<'a> {
+    name: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeepLocal,
+    KeepServer,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkspaceSyncResult {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub pushed: usize,
+    pub conflicts: Vec<WorkspaceConflict>,
+}
+
+/// 서버 변경분을 반영한 뒤 서버로 보내야 할 로컬 변경분
+#[derive(Debug, Default)]
+struct PendingPushes {
+    /// (로컬 id, 이름, 종류, owner_id)
+    create: Vec<(String, String, WorkspaceType, Option<i64>)>,
+    /// (서버 id, 새 이름)
+    rename: Vec<(i64, String)>,
+    delete: Vec<i64>,
+}
+
+fn conflict(ws: &Workspace, kind: WorkspaceConflictKind, server: Option<&ServerWorkspace>) -> WorkspaceConflict {
+    WorkspaceConflict {
+        workspace_id: ws.id.clone(),
+        kind,
+        local_name: ws.name.clone(),
+        server_id: server.map(|s| s.workspace_id).or(ws.server_id),
+        server_name: server.map(|s| s.name.clone()),
+        detected_at_unix: account::now_unix(),
+    }
+}
+
+fn link(ws: &mut Workspace, server: &ServerWorkspace) {
+    ws.server_id = Some(server.workspace_id);
+    ws.synced_name = Some(server.name.clone());
+    ws.sync_state = WorkspaceSyncState::Synced;
+    if server.workspace_type == WorkspaceType::Team {
+        ws.team_id = Some(server.owner_id);
+    }
+}
+
+fn record_conflict(state: &mut WorkspaceState, ws_index: usize, entry: WorkspaceConflict) {
+    state.workspaces[ws_index].sync_state = WorkspaceSyncState::Conflict;
+    state.conflicts.retain(|c| c.workspace_id != entry.workspace_id);
+    state.conflicts.push(entry);
+}
+
+/// 서버 목록을 로컬 상태에 병합하고 서버로 보낼 변경분을 반환
+fn merge_server_workspaces(
+    state: &mut WorkspaceState,
+    server: &[ServerWorkspace],
+    result: &mut WorkspaceSyncResult,
+) -> PendingPushes {
+    let mut pushes = PendingPushes::default();
+    let server_ids: HashSet<i64> = server.iter().map(|s| s.workspace_id).collect();
+
+    // 1. 서버와 연결된 워크스페이스: 이름 3-way 병합, 서버에서 삭제된 것 처리
+    let mut index = 0;
+    while index < state.workspaces.len() {
+        let ws = &state.workspaces[index];
+        let server_id = match ws.server_id {
+            Some(id) if ws.sync_state != WorkspaceSyncState::Conflict => id,
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+
+        match server.iter().find(|s| s.workspace_id == server_id) {
+            Some(remote) => {
+                let base = ws.synced_name.clone().unwrap_or_else(|| remote.name.clone());
+                let local_changed = ws.name != base;
+                let server_changed = remote.name != base;
+
+                if local_changed && server_changed && ws.name != remote.name {
+                    let entry = conflict(ws, WorkspaceConflictKind::NameChanged, Some(remote));
+                    record_conflict(state, index, entry);
+                } else {
+                    let local_name = ws.name.clone();
+                    let ws = &mut state.workspaces[index];
+                    link(ws, remote);
+                    if server_changed && !local_changed {
+                        ws.name = remote.name.clone();
+                        ws.updated_at_unix = account::now_unix();
+                        result.updated += 1;
+                    } else if local_changed && !server_changed {
+                        ws.synced_name = Some(base);
+                        pushes.rename.push((server_id, local_name));
+                    }
+                }
+                index += 1;
+            }
+            None => {
+                let locally_modified = ws.synced_name.as_deref() != Some(ws.name.as_str());
+                if locally_modified {
+                    let entry = conflict(ws, WorkspaceConflictKind::DeletedOnServer, None);
+                    record_conflict(state, index, entry);
+                    index += 1;
+                } else {
+                    state.workspaces.remove(index);
+                    result.removed += 1;
+                }
+            }
+        }
+    }
+
+    // 2. 로컬에 없는 서버 워크스페이스 추가. 오프라인에서 만든 같은 이름의 워크스페이스는 충돌로 표시
+    let linked: HashSet<i64> = state.workspaces.iter().filter_map(|ws| ws.server_id).collect();
+    for remote in server {
+        if linked.contains(&remote.workspace_id)
+            || state.pending_server_deletions.contains(&remote.workspace_id)
+        {
+            continue;
+        }
+
+        let duplicate = state.workspaces.iter().position(|ws| {
+            ws.server_id.is_none()
+                && ws.sync_state == WorkspaceSyncState::LocalOnly
+                && ws.workspace_type == remote.workspace_type
+                && ws.name.trim().eq_ignore_ascii_case(remote.name.trim())
+        });
+        if let Some(dup_index) = duplicate {
+            let entry = conflict(
+                &state.workspaces[dup_index],
+                WorkspaceConflictKind::DuplicateName,
+                Some(remote),
+            );
+            record_conflict(state, dup_index, entry);
+        }
+
+        let mut ws = workspace::new_workspace(remote.name.clone(), remote.workspace_type.clone());
+        link(&mut ws, remote);
+        workspace::push_workspace(state, ws);
+        result.added += 1;
+    }
+
+    // 3. 오프라인에서 만든 워크스페이스는 서버에 생성
+    for ws in &state.workspaces {
+        if ws.server_id.is_some()
+            || ws.sync_state != WorkspaceSyncState::LocalOnly
+            || ws.deleted_at_unix.is_some()
+        {
+            continue;
+        }
+        if ws.workspace_type == WorkspaceType::Team && ws.team_id.is_none() {
+            log::warn!("workspace sync: team workspace {} has no team id yet", ws.id);
+            continue;
+        }
+        pushes.create.push((
+            ws.id.clone(),
+            ws.name.clone(),
+            ws.workspace_type.clone(),
+            ws.team_id,
+        ));
+    }
+
+    // 4. 로컬에서 지운 워크스페이스는 서버에서도 삭제 (이미 없으면 무시)
+    state
+        .pending_server_deletions
+        .retain(|id| server_ids.contains(id));
+    pushes.delete = state.pending_server_deletions.clone();
+
+    pushes
+}
+
+async fn create_on_server(
+    token: &str,
+    name: &str,
+    workspace_type: &WorkspaceType,
+    owner_id: Option<i64>,
+) -> Result<ServerWorkspace, String> {
+    let request = CreateWorkspaceRequest {
+        name,
+        workspace_type,
+        owner_id: if *workspace_type == WorkspaceType::Team {
+            owner_id
+        } else {
+            None
+        },
+    };
+    let response: CreatedWorkspaceResponse =
+        api::post_json(CREATE_WORKSPACE_ENDPOINT, Some(token), &request).await?;
+    Ok(response.workspace)
+}
+
+async fn rename_on_server(token: &str, server_id: i64, name: &str) -> Result<(), String> {
+    let path = format!("{}/{}", CREATE_WORKSPACE_ENDPOINT, server_id);
+    api::patch_json::<_, serde_json::Value>(&path, Some(token), &RenameWorkspaceRequest { name })
+        .await
+        .map(|_| ())
+}
+
+async fn push_changes(
+    app: &tauri::AppHandle,
+    token: &str,
+    pushes: PendingPushes,
+    result: &mut WorkspaceSyncResult,
+) -> Result<(), String> {
+    for (local_id, name, workspace_type, owner_id) in pushes.create {
+        match create_on_server(token, &name, &workspace_type, owner_id).await {
+            Ok(remote) => {
+                workspace::update_state(app, |state| {
+                    if let Some(ws) = state.workspaces.iter_mut().find(|ws| ws.id == local_id) {
+                        link(ws, &remote);
+                    }
+                    Ok(())
+                })?;
+                result.pushed += 1;
+            }
+            Err(e) => log::warn!("workspace sync: failed to create {}: {}", local_id, e),
+        }
+    }
+
+    for (server_id, name) in pushes.rename {
+        match rename_on_server(token, server_id, &name).await {
+            Ok(()) => {
+                workspace::update_state(app, |state| {
+                    if let Some(ws) = state
+                        .workspaces
+                        .iter_mut()
+                        .find(|ws| ws.server_id == Some(server_id))
+                    {
+                        ws.synced_name = Some(name.clone());
+                    }
+                    Ok(())
+                })?;
+                result.pushed += 1;
+            }
+            Err(e) => log::warn!("workspace sync: failed to rename {}: {}", server_id, e),
+        }
+    }
+
+    for server_id in pushes.delete {
+        let path = format!("{}/{}", CREATE_WORKSPACE_ENDPOINT, server_id);
+        match api::delete(&path, Some(token)).await {
+            Ok(()) => {
+                workspace::update_state(app, |state| {
+                    state.pending_server_deletions.retain(|id| *id != server_id);
+                    Ok(())
+                })?;
+                result.pushed += 1;
+            }
+            Err(e) => log::warn!("workspace sync: failed to delete {}: {}", server_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// 서버 워크스페이스와 로컬 상태를 양방향으로 동기화. 재연결 시 프론트엔드가 호출한다.
+#[tauri::command]
+pub async fn sync_workspaces(app: tauri::AppHandle) -> Result<WorkspaceSyncResult, String> {
+    let token = account::access_token(&app)?;
+    let response: ServerWorkspacesResponse =
+        api::get_json(WORKSPACES_ENDPOINT, Some(&token)).await?;
+
+    let mut result = WorkspaceSyncResult::default();
+    let mut pushes = PendingPushes::default();
+    workspace::update_state(&app, |state| {
+        pushes = merge_server_workspaces(state, &response.workspaces, &mut result);
+        Ok(())
+    })?;
+
+    push_changes(&app, &token, pushes, &mut result).await?;
+    workspace_team::refresh_all_rosters(&app).await;
+
+    let state = workspace::update_state(&app, |state| {
+        state.last_synced_at_unix = Some(account::now_unix());
+        Ok(())
+    })?;
+    result.conflicts = state.conflicts;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn list_workspace_conflicts(app: tauri::AppHandle) -> Vec<WorkspaceConflict> {
+    workspace::load_state(&app).conflicts
+}
+
+/// 동기화 충돌을 사용자가 고른 쪽으로 해결
+#[tauri::command]
+pub async fn resolve_workspace_conflict(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    resolution: ConflictResolution,
+) -> Result<WorkspaceState, String> {
+    let state = workspace::load_state(&app);
+    let entry = state
+        .conflicts
+        .iter()
+        .find(|c| c.workspace_id == workspace_id)
+        .cloned()
+        .ok_or_else(|| "Workspace conflict not found".to_string())?;
+    let local = state
+        .workspaces
+        .iter()
+        .find(|ws| ws.id == workspace_id)
+        .cloned()
+        .ok_or_else(|| "Workspace not found".to_string())?;
+
+    // 로컬 쪽을 유지하는 경우 먼저 서버에 반영
+    let mut created: Option<ServerWorkspace> = None;
+    if matches!(resolution, ConflictResolution::KeepLocal) {
+        let token = account::access_token(&app)?;
+        match entry.kind {
+            WorkspaceConflictKind::NameChanged | WorkspaceConflictKind::DuplicateName => {
+                // 같은 이름의 서버 워크스페이스는 새로 만들지 않고 로컬 사본에 연결
+                let server_id = entry
+                    .server_id
+                    .ok_or_else(|| "Workspace is not linked to the server".to_string())?;
+                if entry.server_name.as_deref() != Some(local.name.as_str()) {
+                    rename_on_server(&token, server_id, &local.name).await?;
+                }
+            }
+            WorkspaceConflictKind::DeletedOnServer => {
+                created = Some(
+                    create_on_server(&token, &local.name, &local.workspace_type, local.team_id)
+                        .await?,
+                );
+            }
+        }
+    }
+
+    workspace::update_state(&app, |state| {
+        apply_resolution(state, &entry, resolution, created.as_ref())
+    })
+}
+
+/// 서버 반영이 끝난 충돌 해결 결과를 로컬 상태에 적용
+fn apply_resolution(
+    state: &mut WorkspaceState,
+    entry: &WorkspaceConflict,
+    resolution: ConflictResolution,
+    created: Option<&ServerWorkspace>,
+) -> Result<(), String> {
+    let workspace_id = entry.workspace_id.as_str();
+    state.conflicts.retain(|c| c.workspace_id != workspace_id);
+
+    // 같은 서버 워크스페이스에 연결된 다른 로컬 사본 (DuplicateName 병합 시 추가된 것)
+    let server_copy = entry.server_id.and_then(|server_id| {
+        state
+            .workspaces
+            .iter()
+            .find(|ws| ws.server_id == Some(server_id) && ws.id != workspace_id)
+            .map(|ws| (ws.id.clone(), ws.team_id))
+    });
+
+    let keep_server_removes = matches!(resolution, ConflictResolution::KeepServer)
+        && entry.kind != WorkspaceConflictKind::NameChanged;
+    if keep_server_removes {
+        // 서버 쪽으로 합치면 로컬 사본을 지우고, 현재 워크스페이스였다면 서버 사본으로 전환
+        if state.current_workspace_id.as_deref() == Some(workspace_id) {
+            state.current_workspace_id = server_copy.map(|(id, _)| id);
+        }
+        state.workspaces.retain(|ws| ws.id != workspace_id);
+        return Ok(());
+    }
+
+    let keep_local_adopts = matches!(resolution, ConflictResolution::KeepLocal)
+        && entry.kind == WorkspaceConflictKind::DuplicateName;
+    let mut adopted_team_id = None;
+    if keep_local_adopts {
+        // 로컬 쪽으로 합치면 서버 사본을 지우고 로컬 워크스페이스가 서버 워크스페이스를 이어받는다
+        if let Some((copy_id, team_id)) = server_copy {
+            if state.current_workspace_id.as_deref() == Some(copy_id.as_str()) {
+                state.current_workspace_id = Some(workspace_id.to_string());
+            }
+            state.workspaces.retain(|ws| ws.id != copy_id);
+            adopted_team_id = team_id;
+        }
+    }
+
+    let ws = state
+        .workspaces
+        .iter_mut()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    match (&resolution, created) {
+        (ConflictResolution::KeepServer, _) => {
+            let server_name = entry.server_name.clone().unwrap_or_else(|| ws.name.clone());
+            ws.name = server_name.clone();
+            ws.synced_name = Some(server_name);
+            ws.sync_state = WorkspaceSyncState::Synced;
+        }
+        (ConflictResolution::KeepLocal, Some(remote)) => link(ws, remote),
+        (ConflictResolution::KeepLocal, None) => {
+            if keep_local_adopts {
+                ws.server_id = entry.server_id;
+                ws.team_id = ws.team_id.or(adopted_team_id);
+            }
+            ws.synced_name = Some(ws.name.clone());
+            ws.sync_state = WorkspaceSyncState::Synced;
+        }
+    }
+    ws.updated_at_unix = account::now_unix();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_workspace(workspace_id: i64, name: &str) -> ServerWorkspace {
+        ServerWorkspace {
+            workspace_id,
+            workspace_type: WorkspaceType::Personal,
+            owner_id: 1,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn keep_local_duplicate_adopts_server_workspace() {
+        let mut state = workspace::empty_state();
+        let local = workspace::push_workspace(
+            &mut state,
+            workspace::new_workspace("Work".to_string(), WorkspaceType::Personal),
+        );
+        state.current_workspace_id = Some(local.id.clone());
+        let server = vec![server_workspace(42, "work")];
+
+        let mut result = WorkspaceSyncResult::default();
+        let pushes = merge_server_workspaces(&mut state, &server, &mut result);
+        assert!(pushes.create.is_empty());
+        assert_eq!(state.workspaces.len(), 2);
+        let entry = state.conflicts[0].clone();
+        assert_eq!(entry.kind, WorkspaceConflictKind::DuplicateName);
+
+        apply_resolution(&mut state, &entry, ConflictResolution::KeepLocal, None).unwrap();
+
+        assert!(state.conflicts.is_empty());
+        assert_eq!(state.workspaces.len(), 1);
+        let ws = &state.workspaces[0];
+        assert_eq!(ws.id, local.id);
+        assert_eq!(ws.server_id, Some(42));
+        assert_eq!(ws.synced_name.as_deref(), Some("Work"));
+        assert_eq!(ws.sync_state, WorkspaceSyncState::Synced);
+        assert_eq!(state.current_workspace_id.as_deref(), Some(local.id.as_str()));
+
+        // 다음 동기화에서 새로 만들 워크스페이스가 없어야 한다
+        let server = vec![server_workspace(42, "Work")];
+        let mut result = WorkspaceSyncResult::default();
+        let pushes = merge_server_workspaces(&mut state, &server, &mut result);
+        assert!(pushes.create.is_empty());
+        assert!(pushes.rename.is_empty());
+        assert_eq!(result.added, 0);
+        assert_eq!(state.workspaces.len(), 1);
+    }
+}
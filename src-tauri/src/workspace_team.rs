@@ -0,0 +1,203 @@
+/// Team membership for team workspaces — members and roles from the teams API, cached in
+/// `WorkspaceState.team_rosters` so permission checks in `workspace` also work offline.
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::{self, TeamMember, TeamRole, TeamRoster, WorkspaceType};
+use crate::entitlement::{self, EntitlementLimit, UpgradeRequired};
+use crate::{account, api};
+
+#[derive(Debug, Deserialize)]
+struct TeamMembersResponse {
+    #[serde(default)]
+    members: Vec<TeamMember>,
+}
+
+#[derive(Serialize)]
+struct InviteRequest<'a> {
+    email: &'a str,
+    role: TeamRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamInvitation {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default = "default_invite_role")]
+    pub role: TeamRole,
+}
+
+fn default_invite_role() -> TeamRole {
+    TeamRole::Member
+}
+
+fn members_path(team_id: i64) -> String {
+    format!("/api/teams/{}/members", team_id)
+}
+
+fn invitations_path(team_id: i64) -> String {
+    format!("/api/teams/{}/invitations", team_id)
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// 팀 워크스페이스의 서버 팀 id
+fn team_id_of(app: &tauri::AppHandle, workspace_id: &str) -> Result<i64, String> {
+    let state = workspace::load_state(app);
+    let ws = state
+        .workspaces
+        .iter()
+        .find(|ws| ws.id == workspace_id.trim())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    if ws.workspace_type != WorkspaceType::Team {
+        return Err("Workspace is not a team workspace".to_string());
+    }
+    ws.team_id
+        .ok_or_else(|| "Team workspace is not linked to a team yet".to_string())
+}
+
+/// 서버에서 멤버 목록을 받아 캐시를 갱신
+pub async fn refresh_roster(app: &tauri::AppHandle, team_id: i64) -> Result<TeamRoster, String> {
+    let token = account::access_token(app)?;
+    let response: TeamMembersResponse = api::get_json(&members_path(team_id), Some(&token)).await?;
+
+    let my_member_id = account::load_session(app)
+        .member_id
+        .and_then(|id| id.trim().parse::<i64>().ok());
+    let roster = TeamRoster {
+        team_id,
+        my_role: my_member_id.and_then(|me| {
+            response
+                .members
+                .iter()
+                .find(|m| m.member_id == me)
+                .map(|m| m.role)
+        }),
+        members: response.members,
+        fetched_at_unix: account::now_unix(),
+    };
+
+    workspace::update_state(app, |state| {
+        state.team_rosters.retain(|r| r.team_id != team_id);
+        state.team_rosters.push(roster.clone());
+        Ok(())
+    })?;
+    Ok(roster)
+}
+
+/// 동기화 후 호출 — 연결된 팀 워크스페이스의 멤버 목록을 모두 갱신 (실패는 로그만 남김)
+pub async fn refresh_all_rosters(app: &tauri::AppHandle) {
+    let mut team_ids: Vec<i64> = workspace::load_state(app)
+        .workspaces
+        .iter()
+        .filter(|ws| ws.workspace_type == WorkspaceType::Team)
+        .filter_map(|ws| ws.team_id)
+        .collect();
+    team_ids.sort_unstable();
+    team_ids.dedup();
+
+    for team_id in team_ids {
+        if let Err(e) = refresh_roster(app, team_id).await {
+            log::warn!("workspace team: failed to refresh members of {}: {}", team_id, e);
+        }
+    }
+}
+
+/// 캐시된 멤버 목록을 반환. 캐시가 없거나 `refresh` 이면 서버에서 다시 받는다.
+#[tauri::command]
+pub async fn list_team_members(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    refresh: Option<bool>,
+) -> Result<TeamRoster, String> {
+    let team_id = team_id_of(&app, &workspace_id)?;
+    if !refresh.unwrap_or(false) {
+        let cached = workspace::load_state(&app)
+            .team_rosters
+            .into_iter()
+            .find(|r| r.team_id == team_id);
+        if let Some(roster) = cached {
+            return Ok(roster);
+        }
+    }
+    refresh_roster(&app, team_id).await
+}
+
+/// 이메일로 팀에 초대. 소유자/관리자만 가능하고 플랜의 팀 좌석 수를 넘을 수 없다.
+#[tauri::command]
+pub async fn invite_team_member(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    email: String,
+    role: Option<TeamRole>,
+) -> Result<TeamInvitation, String> {
+    let email = email.trim().to_lowercase();
+    if !is_valid_email(&email) {
+        return Err("A valid email address is required".to_string());
+    }
+    let role = role.unwrap_or(TeamRole::Member);
+    if role == TeamRole::Owner {
+        return Err("Invitations cannot grant the owner role".to_string());
+    }
+
+    let team_id = team_id_of(&app, &workspace_id)?;
+    let roster = match workspace::load_state(&app)
+        .team_rosters
+        .into_iter()
+        .find(|r| r.team_id == team_id)
+    {
+        Some(roster) => roster,
+        None => refresh_roster(&app, team_id).await?,
+    };
+    if !roster.my_role.is_some_and(TeamRole::can_manage) {
+        return Err("Only team owners and admins can invite members".to_string());
+    }
+    if roster
+        .members
+        .iter()
+        .any(|m| m.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(&email)))
+    {
+        return Err("This person is already a team member".to_string());
+    }
+
+    // 좌석은 모든 팀에 걸쳐 센다. 다른 팀에 이미 있는 사람은 좌석을 더 쓰지 않는다
+    let entitlements = entitlement::current(&app, false).await;
+    let state = workspace::load_state(&app);
+    if let Some(seats) = entitlements.team_seats {
+        let seats_in_use = workspace::count_team_seats(&state);
+        if !workspace::holds_team_seat(&state, &email) && seats_in_use as u64 >= seats as u64 {
+            return Err(UpgradeRequired::new(
+                EntitlementLimit::TeamSeats,
+                &entitlements,
+                seats_in_use as u64,
+                seats as u64,
+            )
+            .to_error());
+        }
+    }
+
+    let token = account::access_token(&app)?;
+    let mut invitation: TeamInvitation = api::post_json(
+        &invitations_path(team_id),
+        Some(&token),
+        &InviteRequest { email: &email, role },
+    )
+    .await?;
+    invitation.email = email;
+    invitation.role = role;
+    Ok(invitation)
+}
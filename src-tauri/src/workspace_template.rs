@@ -0,0 +1,324 @@
+/// Workspace templates — a saved workspace structure (settings, tag set, recurring tasks)
+/// that new workspaces can start from.
+///
+/// User templates are JSON files in the `workspace_templates` config directory; built-in
+/// templates ship inside the binary. Tags and tasks live on the server, so the backend only
+/// stores and validates them and hands them back to the frontend to create.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::{account, storage};
+use crate::workspace::{self, Workspace, WorkspaceSettings};
+
+const TEMPLATE_DIR: &str = "workspace_templates";
+const BUILTIN_PREFIX: &str = "builtin:";
+const BUILTIN_TEMPLATES: [&str; 3] = [
+    include_str!("../templates/project.json"),
+    include_str!("../templates/study.json"),
+    include_str!("../templates/routine.json"),
+];
+const MAX_TAGS: usize = 50;
+const MAX_RECURRING_TASKS: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTag {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTask {
+    pub title: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    /// RFC 5545 RRULE (예: `FREQ=WEEKLY;BYDAY=MO`)
+    pub recurrence: String,
+    /// 워크스페이스 타임존 기준 시작 시각 `HH:MM`
+    pub start_time: String,
+    pub duration_minutes: u32,
+    #[serde(default)]
+    pub reminder_minutes: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub builtin: bool,
+    #[serde(default)]
+    pub settings: WorkspaceSettings,
+    #[serde(default)]
+    pub tags: Vec<TemplateTag>,
+    #[serde(default)]
+    pub recurring_tasks: Vec<TemplateTask>,
+    #[serde(default)]
+    pub created_at_unix: i64,
+}
+
+/// 템플릿으로 만든 워크스페이스. 태그와 반복 일정은 프론트엔드가 서버에 생성한다.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceFromTemplate {
+    pub workspace: Workspace,
+    pub tags: Vec<TemplateTag>,
+    pub recurring_tasks: Vec<TemplateTask>,
+}
+
+fn template_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    storage::config_path(app, TEMPLATE_DIR)
+}
+
+fn template_path(app: &tauri::AppHandle, template_id: &str) -> Result<PathBuf, String> {
+    let valid = match template_id.strip_prefix("tpl_") {
+        Some(suffix) => !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_alphanumeric()),
+        None => false,
+    };
+    if !valid {
+        return Err("Template not found".to_string());
+    }
+    Ok(template_dir(app)?.join(format!("{}.json", template_id)))
+}
+
+fn builtin_templates() -> Vec<WorkspaceTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .filter_map(|raw| match serde_json::from_str::<WorkspaceTemplate>(raw) {
+            Ok(mut template) => {
+                template.builtin = true;
+                Some(template)
+            }
+            Err(e) => {
+                log::warn!("workspace template: invalid built-in template: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn user_templates(app: &tauri::AppHandle) -> Vec<WorkspaceTemplate> {
+    let dir = match template_dir(app) {
+        Ok(dir) => dir,
+        Err(_) => return Vec::new(),
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut templates: Vec<WorkspaceTemplate> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let raw = fs::read_to_string(&path).ok()?;
+            match serde_json::from_str::<WorkspaceTemplate>(&raw) {
+                Ok(mut template) => {
+                    template.builtin = false;
+                    Some(template)
+                }
+                Err(e) => {
+                    log::warn!("workspace template: skipping {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    templates.sort_by_key(|t| t.created_at_unix);
+    templates
+}
+
+fn find_template(app: &tauri::AppHandle, template_id: &str) -> Result<WorkspaceTemplate, String> {
+    let template_id = template_id.trim();
+    if template_id.starts_with(BUILTIN_PREFIX) {
+        return builtin_templates()
+            .into_iter()
+            .find(|t| t.id == template_id)
+            .ok_or_else(|| "Template not found".to_string());
+    }
+
+    let raw = fs::read_to_string(template_path(app, template_id)?)
+        .map_err(|_| "Template not found".to_string())?;
+    serde_json::from_str(&raw).map_err(|e| format!("Failed to read template: {}", e))
+}
+
+fn is_valid_time(value: &str) -> bool {
+    match value.split_once(':') {
+        Some((h, m)) if h.len() == 2 && m.len() == 2 => {
+            matches!((h.parse::<u32>(), m.parse::<u32>()), (Ok(h), Ok(m)) if h < 24 && m < 60)
+        }
+        _ => false,
+    }
+}
+
+fn is_valid_recurrence(rule: &str) -> bool {
+    rule.split(';').any(|part| {
+        matches!(
+            part,
+            "FREQ=DAILY" | "FREQ=WEEKLY" | "FREQ=MONTHLY" | "FREQ=YEARLY"
+        )
+    }) && rule.split(';').all(|part| part.contains('='))
+}
+
+fn validate_tags(tags: Vec<TemplateTag>) -> Result<Vec<TemplateTag>, String> {
+    if tags.len() > MAX_TAGS {
+        return Err(format!("A template can have at most {} tags", MAX_TAGS));
+    }
+    let mut validated: Vec<TemplateTag> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let name = tag.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > 30 {
+            return Err("Tag names must be 1 to 30 characters".to_string());
+        }
+        if validated.iter().any(|t| t.name == name) {
+            continue;
+        }
+        let color = workspace::normalize_color("Tag color", Some(tag.color))?
+            .ok_or_else(|| "Tag color is required".to_string())?;
+        validated.push(TemplateTag { name, color });
+    }
+    Ok(validated)
+}
+
+fn validate_tasks(tasks: Vec<TemplateTask>) -> Result<Vec<TemplateTask>, String> {
+    if tasks.len() > MAX_RECURRING_TASKS {
+        return Err(format!(
+            "A template can have at most {} recurring tasks",
+            MAX_RECURRING_TASKS
+        ));
+    }
+    tasks
+        .into_iter()
+        .map(|task| {
+            let title = task.title.trim().to_string();
+            if title.is_empty() {
+                return Err("Recurring task title is required".to_string());
+            }
+            let recurrence = task.recurrence.trim().to_ascii_uppercase();
+            if !is_valid_recurrence(&recurrence) {
+                return Err(format!("Invalid recurrence rule for {}", title));
+            }
+            if !is_valid_time(task.start_time.trim()) {
+                return Err(format!("Start time for {} must be HH:MM", title));
+            }
+            if task.duration_minutes == 0 || task.duration_minutes > 24 * 60 {
+                return Err(format!("Duration for {} must be within a day", title));
+            }
+            Ok(TemplateTask {
+                color: workspace::normalize_color("Task color", task.color)?,
+                content: task.content.filter(|c| !c.trim().is_empty()),
+                start_time: task.start_time.trim().to_string(),
+                recurrence,
+                title,
+                duration_minutes: task.duration_minutes,
+                reminder_minutes: task.reminder_minutes,
+            })
+        })
+        .collect()
+}
+
+/// 기본 제공 템플릿 다음에 사용자 템플릿 (만든 순서)
+#[tauri::command]
+pub fn list_workspace_templates(app: tauri::AppHandle) -> Vec<WorkspaceTemplate> {
+    let mut templates = builtin_templates();
+    templates.extend(user_templates(&app));
+    templates
+}
+
+/// 워크스페이스 설정과 프론트엔드가 넘겨준 태그·반복 일정을 템플릿 파일로 저장
+#[tauri::command]
+pub fn save_workspace_template(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    name: String,
+    description: Option<String>,
+    tags: Vec<TemplateTag>,
+    recurring_tasks: Vec<TemplateTask>,
+) -> Result<WorkspaceTemplate, String> {
+    let name = workspace::validate_workspace_name(&name)?;
+    let source = workspace::load_state(&app)
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id.trim())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+
+    let template = WorkspaceTemplate {
+        id: format!("tpl_{}", Uuid::now_v7().simple()),
+        name,
+        description: description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+        builtin: false,
+        settings: source.settings,
+        tags: validate_tags(tags)?,
+        recurring_tasks: validate_tasks(recurring_tasks)?,
+        created_at_unix: account::now_unix(),
+    };
+
+    let json = serde_json::to_string_pretty(&template)
+        .map_err(|e| format!("Failed to serialize json: {}", e))?;
+    storage::write_file_atomic(&template_path(&app, &template.id)?, json.as_bytes())?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn delete_workspace_template(app: tauri::AppHandle, template_id: String) -> Result<(), String> {
+    if template_id.trim().starts_with(BUILTIN_PREFIX) {
+        return Err("Built-in templates cannot be deleted".to_string());
+    }
+    let path = template_path(&app, template_id.trim())?;
+    if !path.is_file() {
+        return Err("Template not found".to_string());
+    }
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete template: {}", e))
+}
+
+/// 템플릿 설정으로 개인 워크스페이스를 만들고, 서버에 만들 태그·반복 일정을 함께 반환
+#[tauri::command]
+pub async fn create_workspace_from_template(
+    app: tauri::AppHandle,
+    template_id: String,
+    name: Option<String>,
+) -> Result<WorkspaceFromTemplate, String> {
+    let template = find_template(&app, &template_id)?;
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| template.name.clone());
+
+    // 파일을 직접 고쳤을 수도 있으므로 사용 시점에 다시 검증
+    let tags = validate_tags(template.tags)?;
+    let recurring_tasks = validate_tasks(template.recurring_tasks)?;
+    let workspace = workspace::create_personal_workspace(&app, &name, template.settings).await?;
+    Ok(WorkspaceFromTemplate {
+        workspace,
+        tags,
+        recurring_tasks,
+    })
+}
+
+/// 워크스페이스를 복제하고, 프론트엔드가 넘겨준 원본의 태그·반복 일정을 검증해 함께 반환.
+/// 템플릿으로 만들 때와 같이 프론트엔드가 새 워크스페이스에 서버로 생성한다.
+#[tauri::command]
+pub async fn duplicate_workspace(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    name: Option<String>,
+    tags: Option<Vec<TemplateTag>>,
+    recurring_tasks: Option<Vec<TemplateTask>>,
+) -> Result<WorkspaceFromTemplate, String> {
+    // 워크스페이스를 만들기 전에 검증해 실패해도 빈 사본이 남지 않게 한다
+    let tags = validate_tags(tags.unwrap_or_default())?;
+    let recurring_tasks = validate_tasks(recurring_tasks.unwrap_or_default())?;
+    let workspace = workspace::copy_workspace(&app, &workspace_id, name).await?;
+    Ok(WorkspaceFromTemplate {
+        workspace,
+        tags,
+        recurring_tasks,
+    })
+}
@@ -17,7 +17,13 @@ import {
 } from './components/modals'
 import { LoginPage } from './components/auth'
 import { useWorkspaces } from './hooks'
-import { useThemeStore, useAuthStore, useViewStore } from './stores'
+import {
+  useThemeStore,
+  useAuthStore,
+  useViewStore,
+  useCalendarStore,
+  useModalStore,
+} from './stores'
 import { isTauriApp } from './utils/tauri'
 
 interface AlarmTriggeredPayload {
@@ -29,6 +35,13 @@ interface AlarmTriggeredPayload {
   scheduled_start_at_unix: number
 }
 
+// Rust deep_link::DeepLink (serde tag = "route")
+type DeepLinkRoute =
+  | { route: 'task'; task_id: number }
+  | { route: 'workspace'; workspace_id: number | string }
+  | { route: 'date'; date: string }
+  | { route: 'new'; title: string; start: string | null; end: string | null; all_day: boolean }
+
 interface UserPreferences {
   theme: string
   language: string
@@ -116,6 +129,66 @@ function AppContent() {
     }
   }, [t])
 
+  useEffect(() => {
+    if (!isTauriApp()) return
+
+    let unlisten: (() => void) | undefined
+    let cancelled = false
+
+    const handleRoute = (link: DeepLinkRoute) => {
+      switch (link.route) {
+        case 'date':
+          useCalendarStore.getState().setSelectedDate(new Date(`${link.date}T00:00:00`))
+          useViewStore.getState().setView('calendar')
+          break
+        case 'new':
+          useViewStore.getState().openTaskCreate(link.start ? new Date(link.start) : new Date(), {
+            title: link.title,
+            useStartTime: link.start !== null,
+            end: link.end ? new Date(link.end) : null,
+            allDay: link.all_day,
+          })
+          break
+        case 'task': {
+          const task = useCalendarStore.getState().events.find((e) => e.id === link.task_id)
+          if (task) {
+            useModalStore.getState().openDetailModal(task)
+          } else {
+            useViewStore.getState().setView('tasks')
+          }
+          break
+        }
+        case 'workspace':
+          // 워크스페이스 전환은 Rust 에서 이미 처리됨
+          break
+      }
+    }
+
+    const setup = async () => {
+      try {
+        const stop = await listen<DeepLinkRoute>('deep-link://route', (event) => {
+          handleRoute(event.payload)
+        })
+        if (cancelled) {
+          stop()
+          return
+        }
+        unlisten = stop
+        // 리스너 등록 후 알려야 대기 중이던 링크를 놓치지 않는다
+        await invoke('frontend_ready')
+      } catch (error) {
+        console.error('Failed to register deep link listener:', error)
+      }
+    }
+
+    setup()
+
+    return () => {
+      cancelled = true
+      if (unlisten) unlisten()
+    }
+  }, [])
+
   return (
     <div className="flex flex-col h-screen bg-gray-50 dark:bg-gray-900 rounded-lg overflow-hidden">
       <TitleBar />
@@ -2,11 +2,34 @@ import { useState, useEffect } from 'react'
 import { useTranslation } from 'react-i18next'
 import { listen } from '@tauri-apps/api/event'
 import { open } from '@tauri-apps/plugin-shell'
+import { invoke } from '@tauri-apps/api/core'
 import { fetch } from '@tauri-apps/plugin-http'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import { useAuthStore, useThemeStore } from '../../stores'
 
-const API_BASE_URL = import.meta.env.VITE_API_BASE_URL || 'http://localhost:3000'
+interface AuthCompletedEvent {
+  source: 'loopback' | 'deep_link'
+  flow_id: string
+  success: boolean
+  error: string | null
+  error_description: string | null
+}
+
+interface AuthSessionInfo {
+  session: {
+    provider: string | null
+    access_token: string | null
+    refresh_token: string | null
+    member_id: string | null
+    nickname: string | null
+    email: string | null
+  }
+}
+
+interface OAuthFlowStart {
+  authorize_url: string
+  flow_id: string
+}
 
 export function LoginPage() {
   const { t } = useTranslation()
@@ -17,100 +40,63 @@ export function LoginPage() {
   const [pendingProvider, setPendingProvider] = useState<'kakao' | 'google' | null>(null)
   const appWindow = getCurrentWindow()
 
-  // Deep link 리스너 설정
+  // 로그인 완료 리스너 설정 (루프백 서버 / deep link 콜백 모두 Rust 에서 처리 후 전송)
   useEffect(() => {
-    console.log('🔗 Deep link 리스너 등록 중...')
     let unlistenFn: (() => void) | null = null
 
-    // 비동기로 리스너 등록
-    listen<string>('deep-link://new-url', (event) => {
-      console.log('═══════════════════════════════════════════════════')
-      console.log('📥 Deep link 수신:', event.payload)
-      console.log('═══════════════════════════════════════════════════')
+    listen<AuthCompletedEvent>('auth://completed', async (event) => {
+      const result = event.payload
+      console.log('📥 로그인 완료 이벤트:', { source: result.source, success: result.success })
 
-      try {
-        const url = new URL(event.payload)
-
-        // deskcal://auth/callback 체크
-        if (url.hostname !== 'auth' || url.pathname !== '/callback') {
-          console.log('❌ 잘못된 deep link 경로:', url.hostname, url.pathname)
-          return
-        }
-
-        // 백엔드에서 보낸 파라미터 추출
-        const accessToken = url.searchParams.get('accessToken')
-        const refreshToken = url.searchParams.get('refreshToken')
-        const memberId = url.searchParams.get('memberId')
-        const nickname = url.searchParams.get('nickname')
-        const email = url.searchParams.get('email')
-        const providerParam = url.searchParams.get('provider')
-        const errorParam = url.searchParams.get('error')
+      if (!result.success) {
+        console.error('❌ 로그인 에러:', result.error)
+        setError(`${t('auth.loginFailed')} (${result.error_description || result.error})`)
+        setIsLoading(null)
+        setPendingProvider(null)
+        return
+      }
 
-        // 에러 처리
-        if (errorParam) {
-          console.error('❌ 로그인 에러:', errorParam)
-          setError(`Login failed: ${errorParam}`)
-          setIsLoading(null)
-          return
+      try {
+        // 토큰은 이벤트에 담기지 않으므로 저장된 세션에서 읽는다
+        const { session } = await invoke<AuthSessionInfo>('get_auth_session')
+        if (!session.access_token || !session.refresh_token || !session.member_id) {
+          throw new Error('missing required session fields')
         }
 
         const resolvedProvider =
-          (providerParam as 'kakao' | 'google') || pendingProvider || 'kakao'
-
-        // 필수 파라미터 체크
-        if (!accessToken || !refreshToken || !memberId) {
-          console.error('❌ 필수 파라미터 누락:', {
-            accessToken: !!accessToken,
-            refreshToken: !!refreshToken,
-            memberId: !!memberId,
-          })
-          setError('Invalid login response: missing required parameters')
-          setIsLoading(null)
-          return
-        }
-
-        console.log('✅ 로그인 성공:', {
-          memberId,
-          nickname,
-          email: email || 'N/A',
-          provider: resolvedProvider,
-        })
-
-        // Member 객체 생성 및 인증 설정
+          (session.provider as 'kakao' | 'google') || pendingProvider || 'kakao'
         setAuth(
           {
-            memberId: Number(memberId),
-            nickname: nickname || 'User',
-            email: email || undefined,
+            memberId: Number(session.member_id),
+            nickname: session.nickname || 'User',
+            email: session.email || undefined,
             provider: resolvedProvider,
           },
-          accessToken,
-          refreshToken
+          session.access_token,
+          session.refresh_token
         )
 
         setError(null)
         setIsLoading(null)
         setPendingProvider(null)
       } catch (err) {
-        console.error('❌ Deep link 파싱 에러:', err)
+        console.error('❌ 세션 읽기 실패:', err)
         setError('Failed to process login callback')
         setIsLoading(null)
         setPendingProvider(null)
       }
     }).then((fn) => {
       unlistenFn = fn
-      console.log('✅ Deep link 리스너 등록 완료')
     }).catch((err) => {
-      console.error('❌ Deep link 리스너 등록 실패:', err)
+      console.error('❌ 로그인 리스너 등록 실패:', err)
     })
 
     return () => {
       if (unlistenFn) {
         unlistenFn()
-        console.log('🔗 Deep link 리스너 해제')
       }
     }
-  }, [setAuth, pendingProvider])
+  }, [setAuth, pendingProvider, t])
 
   const handleCloseApp = async () => {
     try {
@@ -127,16 +113,15 @@ export function LoginPage() {
     setPendingProvider(provider)
 
     try {
+      // state / PKCE 는 Rust 가 만들고, 로그인 후 deskcal://auth/callback 으로 돌아온다
+      const flow = await invoke<OAuthFlowStart>('start_oauth_server', {
+        provider,
+        redirect: 'deep_link',
+      })
+
       // 백엔드에서 OAuth URL 가져오기
       console.log('📡 백엔드에서 OAuth URL 요청 중...')
-
-      // deskcal:// 스킴 콜백 URL 전달
-      const callbackUrl = 'deskcal://auth/callback'
-      console.log('🔗 Callback URL:', callbackUrl)
-
-      const response = await fetch(
-        `${API_BASE_URL}/api/auth/${provider}/start?callback=${encodeURIComponent(callbackUrl)}`
-      )
+      const response = await fetch(flow.authorize_url)
 
       if (!response.ok) {
         const errorData = await response.json().catch(() => ({}))
@@ -169,6 +154,9 @@ export function LoginPage() {
   }
 
   const handleCancelLogin = () => {
+    invoke('cancel_oauth_flow', { flowId: null }).catch((err) => {
+      console.error('Failed to cancel login:', err)
+    })
     setIsLoading(null)
     setError(null)
     setPendingProvider(null)
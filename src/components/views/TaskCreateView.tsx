@@ -1,6 +1,6 @@
 import { useEffect, useMemo, useState } from 'react'
 import { useTranslation } from 'react-i18next'
-import { format, setHours, setMinutes } from 'date-fns'
+import { addHours, format, isSameDay, setHours, setMinutes } from 'date-fns'
 import { Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle } from '@/components/ui/card'
 import { Button, Input } from '../common'
 import { useAuthStore, useCalendarStore, useViewStore, useWorkspaceStore } from '../../stores'
@@ -13,10 +13,12 @@ import { EMPTY_RICH_CONTENT, serializeRichContent } from '../../utils/richText'
 const HOURS = Array.from({ length: 24 }, (_, i) => i)
 const MINUTES = [0, 5, 10, 15, 20, 25, 30, 35, 40, 45, 50, 55]
 const pad = (n: number) => String(n).padStart(2, '0')
+/** 분 선택지(5분 단위)에 맞춰 내림 */
+const toMinuteOption = (minute: number) => minute - (minute % 5)
 
 export function TaskCreateView() {
   const { t } = useTranslation()
-  const { createTaskDate, closeTaskCreate } = useViewStore()
+  const { createTaskDate, createTaskDraft, closeTaskCreate } = useViewStore()
   const { selectedWorkspaceId, currentMode, selectedTeamId } = useWorkspaceStore()
   const { user } = useAuthStore()
   const { events, setEvents } = useCalendarStore()
@@ -38,6 +40,34 @@ export function TaskCreateView() {
   const [newTagColor, setNewTagColor] = useState('#6366f1')
   const [isTagCreating, setIsTagCreating] = useState(false)
 
+  // deep link 등으로 넘어온 제목·시간을 채운다. 폼은 하루 안의 일정만 다루므로
+  // 다른 날에 끝나는 일정은 시작일 마지막 시각에서 끝낸다
+  useEffect(() => {
+    if (!createTaskDraft) return
+    if (createTaskDraft.title) setTitle(createTaskDraft.title)
+
+    if (createTaskDraft.allDay) {
+      setStartHour(0)
+      setStartMinute(0)
+      setEndHour(23)
+      setEndMinute(55)
+      return
+    }
+    if (!createTaskDraft.useStartTime) return
+
+    const start = createTaskDate ?? new Date()
+    const end = createTaskDraft.end ?? addHours(start, 1)
+    setStartHour(start.getHours())
+    setStartMinute(toMinuteOption(start.getMinutes()))
+    if (isSameDay(start, end)) {
+      setEndHour(end.getHours())
+      setEndMinute(toMinuteOption(end.getMinutes()))
+    } else {
+      setEndHour(23)
+      setEndMinute(55)
+    }
+  }, [createTaskDate, createTaskDraft])
+
   const formattedDate = useMemo(() => format(baseDate, 'yyyy-MM-dd'), [baseDate])
   const ownerType = currentMode === 'TEAM' ? 'team' : 'personal'
   const ownerId = currentMode === 'TEAM' ? selectedTeamId : user?.memberId
@@ -2,18 +2,30 @@ import { create } from 'zustand'
 
 export type ViewType = 'calendar' | 'tasks' | 'files' | 'memo' | 'task_create'
 
+/** 일정 만들기 화면에 미리 채울 값 (deep link 등) */
+export interface TaskCreateDraft {
+  title?: string
+  /** 시작 날짜의 시각도 그대로 쓴다 */
+  useStartTime?: boolean
+  end?: Date | null
+  allDay?: boolean
+}
+
 interface ViewState {
   activeView: ViewType
   createTaskDate: Date | null
+  createTaskDraft: TaskCreateDraft | null
   setView: (view: ViewType) => void
-  openTaskCreate: (date: Date) => void
+  openTaskCreate: (date: Date, draft?: TaskCreateDraft) => void
   closeTaskCreate: () => void
 }
 
 export const useViewStore = create<ViewState>((set) => ({
   activeView: 'calendar',
   createTaskDate: null,
+  createTaskDraft: null,
   setView: (activeView) => set({ activeView }),
-  openTaskCreate: (date) => set({ activeView: 'task_create', createTaskDate: date }),
-  closeTaskCreate: () => set({ activeView: 'calendar', createTaskDate: null }),
+  openTaskCreate: (date, draft) =>
+    set({ activeView: 'task_create', createTaskDate: date, createTaskDraft: draft ?? null }),
+  closeTaskCreate: () => set({ activeView: 'calendar', createTaskDate: null, createTaskDraft: null }),
 }))